@@ -0,0 +1,358 @@
+//! `#[derive(ContractBindings)]` generates typed bindings for a contract from its JSON ABI, so
+//! that supporting a new contract doesn't mean writing its decoder by hand:
+//!
+//! ```ignore
+//! #[derive(ContractBindings)]
+//! #[abi(path = "abi/etherdelta.json", name = "EtherDelta")]
+//! pub struct EtherDelta;
+//! ```
+//!
+//! The ABI path is relative to the crate's `Cargo.toml`, and `name` defaults to the name of
+//! the struct.  This generates:
+//!
+//! * `EtherDeltaAction`, with a variant per function holding its arguments, implementing
+//!   `NamedFunction` (`"EtherDelta.trade"`, immutable for `view`/`pure` functions);
+//! * `EtherDeltaEvent`, with a variant per non-anonymous event holding its parameters;
+//! * `EtherDelta::decode_input`, `decode_action`, `decode_trace_action` and `decode_event`.
+//!
+//! Both enums implement `Serialize` and `Deserialize`, through serde re-exported by
+//! `ethereum-models`.  Names that aren't valid Rust identifiers are a compile error.
+//!
+//! Arguments are typed as `H160` (`address`), `bool`, `U256` (all integers, as raw words),
+//! `H256` (`bytes32`, and hashed indexed event parameters), `Vec<u8>` (other `bytes`), `String`,
+//! or `AbiValue` for arrays and tuples.  Overloaded functions get numbered variants in ABI
+//! order: `SafeTransferFrom`, `SafeTransferFrom2`.
+
+extern crate ethereum_models;
+extern crate proc_macro;
+extern crate proc_macro2;
+#[macro_use]
+extern crate quote;
+extern crate syn;
+
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use ethereum_models::contracts::abi::{Contract, Event, Function, ParamType};
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as Tokens};
+use syn::{DeriveInput, Ident, LitStr};
+
+#[proc_macro_derive(ContractBindings, attributes(abi))]
+pub fn contract_bindings(input: TokenStream) -> TokenStream {
+    match syn::parse::<DeriveInput>(input).and_then(|input| expand(&input)) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into()
+    }
+}
+
+struct Options {
+    path: LitStr,
+    name: String
+}
+
+fn options(input: &DeriveInput) -> syn::Result<Options> {
+    let mut path = None;
+    let mut name = input.ident.to_string();
+
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("abi")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("path") {
+                path = Some(meta.value()?.parse::<LitStr>()?);
+                Ok(())
+            } else if meta.path.is_ident("name") {
+                name = meta.value()?.parse::<LitStr>()?.value();
+                Ok(())
+            } else {
+                Err(meta.error("expected `path` or `name`"))
+            }
+        })?;
+    }
+
+    let path = path.ok_or_else(|| {
+        syn::Error::new(Span::call_site(), "missing `#[abi(path = \"...\")]` attribute")
+    })?;
+
+    Ok(Options { path, name })
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<Tokens> {
+    let options = options(input)?;
+    let file = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap_or_default()).join(options.path.value());
+    let json = fs::read_to_string(&file).map_err(|e| {
+        syn::Error::new(options.path.span(), format!("Couldn't read {}: {}", file.display(), e))
+    })?;
+    let contract = Contract::load(&json).map_err(|e| {
+        syn::Error::new(options.path.span(), format!("Invalid ABI in {}: {}", file.display(), e))
+    })?;
+
+    let ident = &input.ident;
+    let span = options.path.span();
+    let action = parse_ident(&format!("{}Action", options.name), span)?;
+    let event = parse_ident(&format!("{}Event", options.name), span)?;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let abi_file = file.to_string_lossy().into_owned();
+    let functions = functions(&contract.functions, &options.name, &action, span)?;
+    let events = events(&contract.events, &event, span)?;
+    let Generated { variants: action_variants, arms: name_arms, decoders: function_decoders } = functions;
+    let Generated { variants: event_variants, decoders: event_decoders, .. } = events;
+    let name = &options.name;
+
+    Ok(quote! {
+        #[doc = concat!("A decoded call to the ", #name, " contract.")]
+        #[derive(
+            Debug, Clone, PartialEq,
+            ::ethereum_models::__derive::Serialize, ::ethereum_models::__derive::Deserialize
+        )]
+        #[serde(crate = "::ethereum_models::__derive::serde")]
+        pub enum #action {
+            #(#action_variants),*
+        }
+
+        impl ::ethereum_models::contracts::NamedFunction for #action {
+            fn get_function(&self) -> ::ethereum_models::contracts::ContractFunction {
+                match *self {
+                    #(#name_arms),*
+                }
+            }
+        }
+
+        #[doc = concat!("A decoded event emitted by the ", #name, " contract.")]
+        #[derive(
+            Debug, Clone, PartialEq,
+            ::ethereum_models::__derive::Serialize, ::ethereum_models::__derive::Deserialize
+        )]
+        #[serde(crate = "::ethereum_models::__derive::serde")]
+        pub enum #event {
+            #(#event_variants),*
+        }
+
+        impl #impl_generics #ident #ty_generics #where_clause {
+            /// The contract's ABI, parsed on first use.
+            pub fn abi() -> &'static ::ethereum_models::contracts::abi::Contract {
+                static ABI: ::std::sync::OnceLock<::ethereum_models::contracts::abi::Contract> =
+                    ::std::sync::OnceLock::new();
+                ABI.get_or_init(|| {
+                    ::ethereum_models::contracts::abi::Contract::load(include_str!(#abi_file))
+                        .expect("ABI was checked at compile time")
+                })
+            }
+
+            /// Decodes the hex-encoded input of a call to the contract.
+            pub fn decode_input(input: &str) -> Result<#action, ::ethereum_models::Error> {
+                use ::ethereum_models::contracts::abi::{take_value, DecodeMode};
+
+                let method_id = input.get(..10).map(str::to_lowercase).unwrap_or_default();
+                match method_id.as_str() {
+                    #(#function_decoders)*
+                    _ => Err(::ethereum_models::ErrorKind::Decoder(
+                        format!("Unknown {} method: {}", #name, input)
+                    ).into())
+                }
+            }
+
+            pub fn decode_action(tx: &::ethereum_models::objects::Transaction) -> Result<#action, ::ethereum_models::Error> {
+                Self::decode_input(&tx.input)
+            }
+
+            pub fn decode_trace_action(trace: &::ethereum_models::objects::ParityTrace) -> Result<#action, ::ethereum_models::Error> {
+                Self::decode_input(&trace.action.input)
+            }
+
+            pub fn decode_event(log: &::ethereum_models::objects::Log) -> Result<#event, ::ethereum_models::Error> {
+                use ::ethereum_models::contracts::abi::{take_value, DecodeMode};
+
+                let topic = log.topics.first().ok_or_else(|| {
+                    ::ethereum_models::Error::from(::ethereum_models::ErrorKind::Decoder(
+                        format!("{} log without topics", #name)
+                    ))
+                })?;
+                match topic.0 {
+                    #(#event_decoders)*
+                    _ => Err(::ethereum_models::ErrorKind::Decoder(
+                        format!("Unknown {} event: {:?}", #name, topic)
+                    ).into())
+                }
+            }
+        }
+    })
+}
+
+#[derive(Default)]
+struct Generated {
+    variants: Vec<Tokens>,
+    arms: Vec<Tokens>,
+    decoders: Vec<Tokens>
+}
+
+fn functions(functions: &[Function], contract: &str, action: &Ident, span: Span) -> syn::Result<Generated> {
+    let names = variant_names(functions.iter().map(|f| f.name.as_str()), span)?;
+    let mut generated = Generated::default();
+
+    for (i, (function, variant)) in functions.iter().zip(names).enumerate() {
+        let signature = format!("`{}`", function.signature());
+        let types = function.inputs.iter().map(|p| rust_type(&p.kind, false)).collect::<Vec<_>>();
+        let qualified = format!("{}.{}", contract, function.name);
+        let method_id = function.method_id();
+
+        generated.variants.push(if types.is_empty() {
+            quote!(#[doc = #signature] #variant)
+        } else {
+            quote!(#[doc = #signature] #variant(#(#types),*))
+        });
+
+        generated.arms.push(if function.is_constant() {
+            quote!(#action::#variant { .. } => ::ethereum_models::contracts::ContractFunction::Immutable(#qualified.to_string()))
+        } else {
+            quote!(#action::#variant { .. } => ::ethereum_models::contracts::ContractFunction::Mutable(#qualified.to_string()))
+        });
+
+        generated.decoders.push(if types.is_empty() {
+            quote! {
+                #method_id => {
+                    Self::abi().functions[#i].decode_input(input, DecodeMode::Strict)?;
+                    Ok(#action::#variant)
+                }
+            }
+        } else {
+            let takes = types.iter().map(|_| quote!(take_value(&mut values)?));
+            quote! {
+                #method_id => {
+                    let mut values = Self::abi().functions[#i].decode_input(input, DecodeMode::Strict)?.into_iter();
+                    Ok(#action::#variant(#(#takes),*))
+                }
+            }
+        });
+    }
+
+    Ok(generated)
+}
+
+fn events(events: &[Event], enum_name: &Ident, span: Span) -> syn::Result<Generated> {
+    // Anonymous events have no topic to dispatch on.
+    let events = events.iter().enumerate().filter(|&(_, e)| !e.anonymous).collect::<Vec<_>>();
+    let names = variant_names(events.iter().map(|&(_, e)| e.name.as_str()), span)?;
+    let mut generated = Generated::default();
+
+    for ((i, event), variant) in events.into_iter().zip(names) {
+        let signature = format!("`{}`", event.signature());
+        let types = event.inputs.iter().map(|p| rust_type(&p.kind, p.indexed)).collect::<Vec<_>>();
+        let topic = event.topic().0;
+
+        generated.variants.push(if types.is_empty() {
+            quote!(#[doc = #signature] #variant)
+        } else {
+            quote!(#[doc = #signature] #variant(#(#types),*))
+        });
+
+        generated.decoders.push(if types.is_empty() {
+            quote! {
+                [#(#topic),*] => {
+                    Self::abi().events[#i].decode_log(log, DecodeMode::Strict)?;
+                    Ok(#enum_name::#variant)
+                }
+            }
+        } else {
+            let takes = types.iter().map(|_| quote!(take_value(&mut values)?));
+            quote! {
+                [#(#topic),*] => {
+                    let mut values = Self::abi().events[#i].decode_log(log, DecodeMode::Strict)?
+                        .params
+                        .into_iter()
+                        .map(|p| p.value);
+                    Ok(#enum_name::#variant(#(#takes),*))
+                }
+            }
+        });
+    }
+
+    Ok(generated)
+}
+
+/// The Rust type of a decoded parameter.  Indexed reference types are only available as the
+/// hash stored in their topic.
+fn rust_type(kind: &ParamType, indexed: bool) -> Tokens {
+    match *kind {
+        ParamType::Address => quote!(::ethereum_models::types::H160),
+        ParamType::Bool => quote!(bool),
+        ParamType::Int(_) | ParamType::Uint(_) => quote!(::ethereum_models::types::U256),
+        ParamType::FixedBytes(32) => quote!(::ethereum_models::types::H256),
+        ParamType::FixedBytes(_) => quote!(Vec<u8>),
+        _ if indexed => quote!(::ethereum_models::types::H256),
+        ParamType::Bytes => quote!(Vec<u8>),
+        ParamType::String => quote!(String),
+        _ => quote!(::ethereum_models::contracts::abi::AbiValue)
+    }
+}
+
+/// Camel-cased variant names, numbering repeated names from 2.
+fn variant_names<'a, I: Iterator<Item = &'a str>>(names: I, span: Span) -> syn::Result<Vec<Ident>> {
+    let mut seen = HashMap::new();
+
+    names.map(|name| {
+        let base = camel_case(name);
+        let count = seen.entry(base.clone()).or_insert(0usize);
+        *count += 1;
+
+        let name = if *count == 1 { base } else { format!("{}{}", base, *count) };
+        parse_ident(&name, span)
+    }).collect()
+}
+
+/// Names from the ABI or the attribute can be keywords (`self` becomes `Self`) or start with a
+/// digit; those fail with an error pointing at the ABI path rather than panicking.
+fn parse_ident(name: &str, span: Span) -> syn::Result<Ident> {
+    syn::parse_str::<Ident>(name)
+        .map(|mut ident| {
+            ident.set_span(span);
+            ident
+        })
+        .map_err(|_| syn::Error::new(span, format!("`{}` is not a valid Rust identifier", name)))
+}
+
+fn camel_case(name: &str) -> String {
+    let mut camel = String::with_capacity(name.len());
+    let mut upper = true;
+
+    for c in name.chars() {
+        if c == '_' || c == '$' {
+            upper = true;
+        } else if upper {
+            camel.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            camel.push(c);
+        }
+    }
+
+    if camel.is_empty() {
+        camel.push_str("Unnamed");
+    }
+    camel
+}
+
+#[cfg(test)]
+mod tests {
+    use proc_macro2::Span;
+
+    use super::{camel_case, variant_names};
+
+    #[test]
+    fn names_variants() {
+        assert_eq!(camel_case("balanceOf"), "BalanceOf");
+        assert_eq!(camel_case("_set_owner"), "SetOwner");
+
+        let names = variant_names(vec!["safeTransferFrom", "approve", "safeTransferFrom"].into_iter(), Span::call_site())
+            .unwrap()
+            .iter()
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["SafeTransferFrom", "Approve", "SafeTransferFrom2"]);
+
+        assert!(variant_names(vec!["self"].into_iter(), Span::call_site()).is_err());
+        assert!(variant_names(vec!["1st"].into_iter(), Span::call_site()).is_err());
+    }
+}
@@ -0,0 +1,145 @@
+extern crate ethereum_models;
+#[macro_use]
+extern crate ethereum_models_derive;
+extern crate serde_json;
+
+use std::str::FromStr;
+
+use ethereum_models::contracts::etherdelta::constants::{
+    ETHERDELTA_ADDRESS, ETHERDELTA_DEPOSIT_TOPIC, ETHERDELTA_ORDER_TOPIC, ETHERDELTA_TRADE_TOPIC,
+    ETHERDELTA_WITHDRAW_TOPIC, DEPOSIT_ID, ORDER_ID, WITHDRAW_ID
+};
+use ethereum_models::contracts::etherdelta::decoder::EtherDeltaDecoder;
+use ethereum_models::contracts::etherdelta::EtherDeltaAction as Handwritten;
+use ethereum_models::contracts::NamedFunction;
+use ethereum_models::objects::{Log, ParityTrace};
+use ethereum_models::types::{H160, H256, U256};
+
+#[derive(ContractBindings)]
+#[abi(path = "../src/contracts/etherdelta/abi.json")]
+pub struct EtherDelta;
+
+fn proxy_traces() -> Vec<ParityTrace> {
+    serde_json::from_str(include_str!("../../test_data/etherdelta_proxy_trace.json")).unwrap()
+}
+
+fn log(topic: H256, data: String) -> Log {
+    Log { address: *ETHERDELTA_ADDRESS, topics: vec![topic], data, ..Default::default() }
+}
+
+fn word(n: u64) -> String {
+    format!("{:064x}", n)
+}
+
+fn address(n: u64) -> H160 {
+    H160::from(H256::from(n))
+}
+
+#[test]
+fn decodes_traced_calls_like_the_handwritten_decoder() {
+    for trace in &proxy_traces()[1..] {
+        let generated = EtherDelta::decode_trace_action(trace).unwrap();
+        let handwritten = EtherDeltaDecoder::decode_trace_action(trace).unwrap();
+
+        assert_eq!(generated.get_function().to_string(), handwritten.get_function().to_string());
+
+        match (generated, handwritten) {
+            (EtherDeltaAction::BalanceOf(token, user), Handwritten::BalanceOf(t, u)) => assert_eq!((token, user), (t, u)),
+            (EtherDeltaAction::AvailableVolume(token_get, amount_get, _, _, _, nonce, user, v, r, _),
+             Handwritten::AvailableVolume(order, u)) => {
+                assert_eq!((token_get, amount_get, nonce, user, v), (order.token_get, order.amount_get, order.nonce, u, order.v));
+                assert_eq!(r, H256::from_str(order.r.trim_start_matches("0x")).unwrap());
+            },
+            (EtherDeltaAction::Trade(token_get, amount_get, token_give, amount_give, expires, nonce, user, v, r, s, amount),
+             Handwritten::Trade(order, u, a)) => {
+                assert_eq!(
+                    (token_get, amount_get, token_give, amount_give, expires, nonce, v),
+                    (order.token_get, order.amount_get, order.token_give, order.amount_give, order.expires, order.nonce, order.v)
+                );
+                assert_eq!((user, amount), (u, a));
+                assert_eq!(r, H256::from_str(order.r.trim_start_matches("0x")).unwrap());
+                assert_eq!(s, H256::from_str(order.s.trim_start_matches("0x")).unwrap());
+            },
+            (generated, handwritten) => panic!("{:?} decoded as {:?}", handwritten, generated)
+        }
+    }
+}
+
+#[test]
+fn decodes_calls() {
+    let order = format!(
+        "{}{:0>64}{}{}{}{}{}",
+        *ORDER_ID, "e41d2489571d322189246dafa5ebde1f4699f498", word(1000), word(0), word(25), word(5_000_000), word(7)
+    );
+    let zrx = H160::from_str("e41d2489571d322189246dafa5ebde1f4699f498").unwrap();
+
+    match EtherDelta::decode_input(&order).unwrap() {
+        EtherDeltaAction::Order(token_get, amount_get, token_give, amount_give, expires, nonce) => {
+            assert_eq!((token_get, amount_get), (zrx, U256::from(1000)));
+            assert_eq!((token_give, amount_give), (H160::zero(), U256::from(25)));
+            assert_eq!((expires, nonce), (U256::from(5_000_000), U256::from(7)));
+        },
+        other => panic!("Expected an order, got {:?}", other)
+    }
+
+    assert_eq!(EtherDelta::decode_input(&DEPOSIT_ID).unwrap(), EtherDeltaAction::Deposit);
+    assert_eq!(
+        EtherDelta::decode_input(&format!("{}{}", *WITHDRAW_ID, word(42))).unwrap(),
+        EtherDeltaAction::Withdraw(U256::from(42))
+    );
+}
+
+#[test]
+fn serializes_decoded_values() {
+    for trace in &proxy_traces()[1..] {
+        let action = EtherDelta::decode_trace_action(trace).unwrap();
+        let json = serde_json::to_string(&action).unwrap();
+
+        assert_eq!(serde_json::from_str::<EtherDeltaAction>(&json).unwrap(), action);
+    }
+
+    let event = EtherDeltaEvent::Deposit(address(1), address(2), U256::from(3), U256::from(4));
+    let json = serde_json::to_string(&event).unwrap();
+    assert_eq!(serde_json::from_str::<EtherDeltaEvent>(&json).unwrap(), event);
+}
+
+#[test]
+fn decodes_events() {
+    let zrx = "e41d2489571d322189246dafa5ebde1f4699f498";
+    let data = format!("0x{:0>64}{:064x}{:0>64}{:064x}{:0>64}{:0>64}", zrx, 1000, "0", 25, "1", "2");
+
+    match EtherDelta::decode_event(&log(*ETHERDELTA_TRADE_TOPIC, data)).unwrap() {
+        EtherDeltaEvent::Trade(token_get, amount_get, token_give, amount_give, get, give) => {
+            assert_eq!(token_get, H160::from_str(zrx).unwrap());
+            assert_eq!(amount_get, U256::from(1000));
+            assert_eq!(token_give, H160::zero());
+            assert_eq!(amount_give, U256::from(25));
+            assert_eq!(get, H160::from_str(&format!("{:0>40}", 1)).unwrap());
+            assert_eq!(give, H160::from_str(&format!("{:0>40}", 2)).unwrap());
+        },
+        other => panic!("Expected a trade, got {:?}", other)
+    }
+
+    let data = format!("0x{:0>64}{}{}{}{}{}{:0>64}", zrx, word(1000), word(0), word(25), word(5_000_000), word(7), "1");
+    match EtherDelta::decode_event(&log(*ETHERDELTA_ORDER_TOPIC, data)).unwrap() {
+        EtherDeltaEvent::Order(token_get, amount_get, token_give, amount_give, expires, nonce, user) => {
+            assert_eq!((token_get, amount_get), (H160::from_str(zrx).unwrap(), U256::from(1000)));
+            assert_eq!((token_give, amount_give), (H160::zero(), U256::from(25)));
+            assert_eq!((expires, nonce, user), (U256::from(5_000_000), U256::from(7), address(1)));
+        },
+        other => panic!("Expected an order, got {:?}", other)
+    }
+
+    let data = format!("0x{:0>64}{:0>64}{}{}", "0", "1", word(50), word(80));
+    assert_eq!(
+        EtherDelta::decode_event(&log(*ETHERDELTA_DEPOSIT_TOPIC, data.clone())).unwrap(),
+        EtherDeltaEvent::Deposit(H160::zero(), address(1), U256::from(50), U256::from(80))
+    );
+    assert_eq!(
+        EtherDelta::decode_event(&log(*ETHERDELTA_WITHDRAW_TOPIC, data)).unwrap(),
+        EtherDeltaEvent::Withdraw(H160::zero(), address(1), U256::from(50), U256::from(80))
+    );
+
+    assert!(EtherDelta::decode_event(&log(H256::zero(), "0x".to_string())).is_err());
+    assert!(EtherDelta::decode_input("0xdeadbeef").is_err());
+}
@@ -0,0 +1,92 @@
+use error::{Error, ErrorKind};
+use types::{H160, H256, U256};
+
+use super::types::AbiValue;
+
+/// Conversion of a decoded value into a Rust type, used by the code generated by
+/// `ethereum-models-derive`.
+pub trait FromAbiValue: Sized {
+    fn from_abi(value: AbiValue) -> Result<Self, Error>;
+}
+
+impl FromAbiValue for AbiValue {
+    fn from_abi(value: AbiValue) -> Result<AbiValue, Error> {
+        Ok(value)
+    }
+}
+
+impl FromAbiValue for H160 {
+    fn from_abi(value: AbiValue) -> Result<H160, Error> {
+        value.to_address().ok_or_else(|| mismatch("address", &value))
+    }
+}
+
+impl FromAbiValue for bool {
+    fn from_abi(value: AbiValue) -> Result<bool, Error> {
+        value.to_bool().ok_or_else(|| mismatch("bool", &value))
+    }
+}
+
+impl FromAbiValue for U256 {
+    fn from_abi(value: AbiValue) -> Result<U256, Error> {
+        value.to_uint().ok_or_else(|| mismatch("integer", &value))
+    }
+}
+
+/// `bytes32` values, and indexed event parameters that are stored as hashes.
+impl FromAbiValue for H256 {
+    fn from_abi(value: AbiValue) -> Result<H256, Error> {
+        match value {
+            AbiValue::FixedBytes(ref b) if b.len() == 32 => Ok(H256::from_slice(b)),
+            _ => Err(mismatch("bytes32", &value))
+        }
+    }
+}
+
+impl FromAbiValue for Vec<u8> {
+    fn from_abi(value: AbiValue) -> Result<Vec<u8>, Error> {
+        match value {
+            AbiValue::FixedBytes(b) | AbiValue::Bytes(b) => Ok(b),
+            _ => Err(mismatch("bytes", &value))
+        }
+    }
+}
+
+impl FromAbiValue for String {
+    fn from_abi(value: AbiValue) -> Result<String, Error> {
+        match value {
+            AbiValue::String(s) => Ok(s),
+            _ => Err(mismatch("string", &value))
+        }
+    }
+}
+
+/// Converts the next of a sequence of decoded values.
+pub fn take_value<T, I>(values: &mut I) -> Result<T, Error>
+    where T: FromAbiValue, I: Iterator<Item = AbiValue>
+{
+    values.next()
+        .ok_or_else(|| ErrorKind::Decoder("Missing decoded value".to_string()).into())
+        .and_then(T::from_abi)
+}
+
+fn mismatch(expected: &str, value: &AbiValue) -> Error {
+    ErrorKind::Decoder(format!("Expected {}, got {:?}", expected, value)).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use types::{H256, U256};
+    use contracts::abi::AbiValue;
+    use super::{take_value, FromAbiValue};
+
+    #[test]
+    fn converts_values() {
+        let mut values = vec![AbiValue::Uint(U256::from(7)), AbiValue::FixedBytes(vec![1; 32])].into_iter();
+
+        assert_eq!(take_value::<U256, _>(&mut values).unwrap(), U256::from(7));
+        assert_eq!(take_value::<H256, _>(&mut values).unwrap(), H256::from_slice(&[1; 32]));
+        assert!(take_value::<U256, _>(&mut values).is_err());
+        assert!(String::from_abi(AbiValue::Bool(true)).is_err());
+    }
+}
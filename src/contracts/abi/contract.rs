@@ -0,0 +1,445 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use fixed_hash::clean_0x;
+use rustc_serialize::hex::ToHex;
+use serde_json;
+use tiny_keccak;
+
+use error::{Error, ErrorKind};
+use types::H256;
+
+use super::decoder::{decode_call_input, decode_hex, DecodeMode};
+use super::encoder::encode;
+use super::types::{AbiValue, ParamType};
+
+/// Keccak-256 hash, as used for method ids and event topics.
+pub fn keccak256(data: &[u8]) -> H256 {
+    H256::from(tiny_keccak::keccak256(data))
+}
+
+/// A function, error or constructor parameter.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Param {
+    pub name: String,
+    pub kind: ParamType,
+    /// The Solidity type the parameter was declared with, e.g. `struct Order`, if the
+    /// compiler provided it.
+    pub internal_type: Option<String>
+}
+
+/// An event parameter.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventParam {
+    pub name: String,
+    pub kind: ParamType,
+    /// Indexed parameters are stored in the log's topics rather than its data.
+    pub indexed: bool
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StateMutability {
+    Pure,
+    View,
+    NonPayable,
+    Payable
+}
+
+/// A function.  Its selector is hashed once by `new`, so build it with `new` rather than
+/// changing the name or inputs of an existing one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Function {
+    pub name: String,
+    pub inputs: Vec<Param>,
+    pub outputs: Vec<Param>,
+    pub state_mutability: StateMutability,
+    selector: [u8; 4]
+}
+
+impl Function {
+    pub fn new(name: String, inputs: Vec<Param>, outputs: Vec<Param>, state_mutability: StateMutability) -> Function {
+        let selector = selector(&signature(&name, inputs.iter().map(|p| &p.kind)));
+        Function { name, inputs, outputs, state_mutability, selector }
+    }
+
+    /// Canonical signature, e.g. `withdraw(uint256)`.
+    pub fn signature(&self) -> String {
+        signature(&self.name, self.inputs.iter().map(|p| &p.kind))
+    }
+
+    pub fn selector(&self) -> [u8; 4] {
+        self.selector
+    }
+
+    /// `0x`-prefixed hex selector, in the same form as a transaction input's first 10
+    /// characters.
+    pub fn method_id(&self) -> String {
+        format!("0x{}", self.selector().to_hex())
+    }
+
+    /// Does calling this function leave the contract's storage untouched?
+    pub fn is_constant(&self) -> bool {
+        matches!(self.state_mutability, StateMutability::Pure | StateMutability::View)
+    }
+
+    /// Calldata for a call with the given arguments: the selector followed by the encoded
+    /// arguments, which must match the function's inputs.
+    pub fn encode_input(&self, args: &[AbiValue]) -> Result<Vec<u8>, Error> {
+        if args.len() != self.inputs.len() {
+            return Err(ErrorKind::Abi(format!(
+                "{} takes {} arguments, got {}", self.signature(), self.inputs.len(), args.len()
+            )).into());
+        }
+
+        if let Some((i, param)) = self.inputs.iter().enumerate().find(|(i, p)| !args[*i].type_check(&p.kind)) {
+            return Err(ErrorKind::Abi(format!(
+                "Argument {} of {} is not a valid {}: {:?}", i, self.signature(), param.kind, args[i]
+            )).into());
+        }
+
+        let mut data = self.selector().to_vec();
+        data.extend(encode(args));
+        Ok(data)
+    }
+
+    /// `0x`-prefixed hex calldata, as used by `TransactionCall::data`.
+    pub fn encode_input_hex(&self, args: &[AbiValue]) -> Result<String, Error> {
+        self.encode_input(args).map(|data| format!("0x{}", data.to_hex()))
+    }
+
+    /// Decodes the arguments of a call to this function from its hex-encoded input.
+    pub fn decode_input(&self, input: &str, mode: DecodeMode) -> Result<Vec<AbiValue>, Error> {
+        if parse_selector(clean_0x(input)) != Some(self.selector) {
+            return Err(ErrorKind::Decoder(format!("Input is not a call to {}: {}", self.signature(), input)).into());
+        }
+        decode_call_input(&kinds(&self.inputs), input, mode)
+    }
+
+    /// Decodes the hex-encoded return data of an `eth_call` to this function.
+    pub fn decode_output(&self, output: &str, mode: DecodeMode) -> Result<Vec<AbiValue>, Error> {
+        decode_hex(&kinds(&self.outputs), output, mode)
+    }
+}
+
+/// An event.  Like a function's selector, its topic is hashed once by `new`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    pub name: String,
+    pub inputs: Vec<EventParam>,
+    /// Anonymous events don't store their topic hash in the log's first topic.
+    pub anonymous: bool,
+    topic: H256
+}
+
+impl Event {
+    pub fn new(name: String, inputs: Vec<EventParam>, anonymous: bool) -> Event {
+        let topic = keccak256(signature(&name, inputs.iter().map(|p| &p.kind)).as_bytes());
+        Event { name, inputs, anonymous, topic }
+    }
+
+    /// Canonical signature, e.g. `Withdraw(address,address,uint256,uint256)`.
+    pub fn signature(&self) -> String {
+        signature(&self.name, self.inputs.iter().map(|p| &p.kind))
+    }
+
+    /// The event's topic0.
+    pub fn topic(&self) -> H256 {
+        self.topic
+    }
+}
+
+/// A custom error, as reverted with by Solidity >= 0.8.4.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AbiError {
+    pub name: String,
+    pub inputs: Vec<Param>
+}
+
+impl AbiError {
+    pub fn signature(&self) -> String {
+        signature(&self.name, self.inputs.iter().map(|p| &p.kind))
+    }
+
+    pub fn selector(&self) -> [u8; 4] {
+        selector(&self.signature())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Constructor {
+    pub inputs: Vec<Param>,
+    pub state_mutability: StateMutability
+}
+
+/// A contract's interface, as loaded from its JSON ABI.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Contract {
+    pub constructor: Option<Constructor>,
+    pub functions: Vec<Function>,
+    pub events: Vec<Event>,
+    pub errors: Vec<AbiError>,
+    /// The fallback function's state mutability, if the contract defines one.
+    pub fallback: Option<StateMutability>,
+    /// Does the contract define a `receive` function?
+    pub receive: bool
+}
+
+impl Contract {
+    /// Parses a standard JSON ABI, i.e. an array of function, event, error, constructor,
+    /// fallback and receive descriptions.  Pre-0.4.16 ABIs using `constant` and `payable`
+    /// instead of `stateMutability` are supported.
+    pub fn load(json: &str) -> Result<Contract, Error> {
+        let entries = serde_json::from_str::<Vec<RawEntry>>(json)?;
+        let mut contract = Contract::default();
+
+        for entry in entries {
+            let state_mutability = entry.state_mutability();
+
+            match entry.entry_type.as_deref().unwrap_or("function") {
+                "function" => contract.functions.push(Function::new(
+                    entry.name,
+                    params(entry.inputs)?,
+                    params(entry.outputs)?,
+                    state_mutability
+                )),
+                "event" => contract.events.push(Event::new(
+                    entry.name,
+                    entry.inputs.into_iter()
+                        .map(|p| {
+                            let indexed = p.indexed;
+                            p.into_param().map(|p| EventParam { name: p.name, kind: p.kind, indexed })
+                        })
+                        .collect::<Result<Vec<EventParam>, Error>>()?,
+                    entry.anonymous
+                )),
+                "error" => contract.errors.push(AbiError {
+                    name: entry.name,
+                    inputs: params(entry.inputs)?
+                }),
+                "constructor" => contract.constructor = Some(Constructor {
+                    inputs: params(entry.inputs)?,
+                    state_mutability
+                }),
+                "fallback" => contract.fallback = Some(state_mutability),
+                "receive" => contract.receive = true,
+                other => return Err(ErrorKind::Abi(format!("Unknown ABI entry type: {}", other)).into())
+            }
+        }
+
+        Ok(contract)
+    }
+
+    pub fn load_file<P: AsRef<Path>>(path: P) -> Result<Contract, Error> {
+        let mut json = String::new();
+        File::open(path)?.read_to_string(&mut json)?;
+        Contract::load(&json)
+    }
+
+    /// The first function with the given name.  Use `functions_named` for overloads.
+    pub fn function(&self, name: &str) -> Option<&Function> {
+        self.functions.iter().find(|f| f.name == name)
+    }
+
+    pub fn functions_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Function> + 'a {
+        self.functions.iter().filter(move |f| f.name == name)
+    }
+
+    /// The function called by a transaction input or method id, e.g. `0x0a19b14a...`.
+    pub fn function_by_method_id(&self, input: &str) -> Option<&Function> {
+        let selector = parse_selector(clean_0x(input))?;
+        self.functions.iter().find(|f| f.selector == selector)
+    }
+
+    pub fn event(&self, name: &str) -> Option<&Event> {
+        self.events.iter().find(|e| e.name == name)
+    }
+
+    pub fn event_by_topic(&self, topic: &H256) -> Option<&Event> {
+        self.events.iter().find(|e| !e.anonymous && e.topic() == *topic)
+    }
+
+    pub fn error(&self, name: &str) -> Option<&AbiError> {
+        self.errors.iter().find(|e| e.name == name)
+    }
+}
+
+fn signature<'a, I>(name: &str, kinds: I) -> String
+    where I: Iterator<Item = &'a ParamType>
+{
+    let kinds = kinds.map(|k| k.to_string()).collect::<Vec<String>>();
+    format!("{}({})", name, kinds.join(","))
+}
+
+fn selector(signature: &str) -> [u8; 4] {
+    let hash = keccak256(signature.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// The selector in the first 8 hex digits of unprefixed calldata, in either case.
+fn parse_selector(input: &str) -> Option<[u8; 4]> {
+    let digits = input.get(..8).filter(|d| d.bytes().all(|b| b.is_ascii_hexdigit()))?;
+    let mut selector = [0u8; 4];
+    for (i, byte) in selector.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&digits[i * 2..i * 2 + 2], 16).ok()?;
+    }
+    Some(selector)
+}
+
+fn kinds(params: &[Param]) -> Vec<ParamType> {
+    params.iter().map(|p| p.kind.clone()).collect()
+}
+
+fn params(raw: Vec<RawParam>) -> Result<Vec<Param>, Error> {
+    raw.into_iter().map(RawParam::into_param).collect()
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawEntry {
+    #[serde(rename = "type")]
+    entry_type: Option<String>,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    inputs: Vec<RawParam>,
+    #[serde(default)]
+    outputs: Vec<RawParam>,
+    state_mutability: Option<StateMutability>,
+    constant: Option<bool>,
+    payable: Option<bool>,
+    #[serde(default)]
+    anonymous: bool
+}
+
+impl RawEntry {
+    fn state_mutability(&self) -> StateMutability {
+        match (self.state_mutability, self.constant, self.payable) {
+            (Some(m), _, _) => m,
+            (None, Some(true), _) => StateMutability::View,
+            (None, _, Some(true)) => StateMutability::Payable,
+            _ => StateMutability::NonPayable
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawParam {
+    #[serde(default)]
+    name: String,
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    components: Vec<RawParam>,
+    #[serde(default)]
+    indexed: bool,
+    internal_type: Option<String>
+}
+
+impl RawParam {
+    fn into_param(self) -> Result<Param, Error> {
+        let kind = if self.kind.starts_with("tuple") {
+            // `tuple`, `tuple[]`, `tuple[2][]`...: the member types are in `components`.
+            let members = self.components.into_iter()
+                .map(|c| c.into_param().map(|p| p.kind))
+                .collect::<Result<Vec<ParamType>, Error>>()?;
+            format!("{}{}", ParamType::Tuple(members), &self.kind["tuple".len()..]).parse()?
+        } else {
+            self.kind.parse()?
+        };
+
+        Ok(Param { name: self.name, kind, internal_type: self.internal_type })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use types::H256;
+    use contracts::abi::{DecodeMode, ParamType};
+    use super::{keccak256, Contract, StateMutability};
+
+    const ABI: &str = r#"[
+        {"type":"constructor","inputs":[{"name":"owner","type":"address"}],"stateMutability":"nonpayable"},
+        {"type":"function","name":"transfer","inputs":[{"name":"to","type":"address"},{"name":"value","type":"uint256"}],"outputs":[{"name":"","type":"bool"}],"stateMutability":"nonpayable"},
+        {"type":"function","name":"balanceOf","inputs":[{"name":"owner","type":"address"}],"outputs":[{"name":"","type":"uint256"}],"stateMutability":"view"},
+        {"type":"function","name":"fill","inputs":[{"name":"orders","type":"tuple[]","internalType":"struct Order[]","components":[{"name":"maker","type":"address"},{"name":"amounts","type":"uint256[2]"}]}],"outputs":[],"stateMutability":"payable"},
+        {"type":"event","name":"Transfer","inputs":[{"name":"from","type":"address","indexed":true},{"name":"to","type":"address","indexed":true},{"name":"value","type":"uint256","indexed":false}],"anonymous":false},
+        {"type":"error","name":"InsufficientBalance","inputs":[{"name":"available","type":"uint256"},{"name":"required","type":"uint256"}]},
+        {"type":"fallback","stateMutability":"nonpayable"},
+        {"type":"receive","stateMutability":"payable"}
+    ]"#;
+
+    #[test]
+    fn hashes_with_keccak() {
+        assert_eq!(
+            keccak256(b""),
+            H256::from_str("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470").unwrap()
+        );
+    }
+
+    #[test]
+    fn loads_all_entry_types() {
+        let contract = Contract::load(ABI).unwrap();
+
+        assert_eq!(contract.functions.len(), 3);
+        assert_eq!(contract.events.len(), 1);
+        assert_eq!(contract.errors.len(), 1);
+        assert!(contract.constructor.is_some());
+        assert_eq!(contract.fallback, Some(StateMutability::NonPayable));
+        assert!(contract.receive);
+        assert!(contract.function("balanceOf").unwrap().is_constant());
+        assert!(!contract.function("transfer").unwrap().is_constant());
+    }
+
+    #[test]
+    fn computes_selectors_and_topics() {
+        let contract = Contract::load(ABI).unwrap();
+
+        assert_eq!(contract.function("transfer").unwrap().method_id(), "0xa9059cbb");
+        assert_eq!(contract.function("balanceOf").unwrap().method_id(), "0x70a08231");
+        assert_eq!(contract.error("InsufficientBalance").unwrap().selector(), [0xcf, 0x47, 0x91, 0x81]);
+        assert_eq!(
+            contract.event("Transfer").unwrap().topic(),
+            H256::from_str("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef").unwrap()
+        );
+        assert_eq!(
+            contract.function_by_method_id("0xa9059cbb0000").map(|f| f.name.as_str()),
+            Some("transfer")
+        );
+        assert!(contract.function_by_method_id("0xA9059CBB").is_some());
+        for invalid in &["0xa9059c", "0x+9059cbb", "0x\u{e9}\u{e9}\u{e9}\u{e9}\u{e9}"] {
+            assert!(contract.function_by_method_id(invalid).is_none(), "{}", invalid);
+        }
+
+        let transfer = contract.function("transfer").unwrap();
+        let input = format!("0xA9059CBB{:064x}{:064x}", 1, 2);
+        assert_eq!(transfer.decode_input(&input, DecodeMode::Strict).unwrap().len(), 2);
+        assert!(transfer.decode_input("0x\u{e9}\u{e9}\u{e9}\u{e9}\u{e9}", DecodeMode::Strict).is_err());
+    }
+
+    #[test]
+    fn expands_tuple_components() {
+        let contract = Contract::load(ABI).unwrap();
+        let fill = contract.function("fill").unwrap();
+
+        assert_eq!(
+            fill.inputs[0].kind,
+            ParamType::Array(Box::new(ParamType::Tuple(vec![
+                ParamType::Address,
+                ParamType::FixedArray(Box::new(ParamType::Uint(256)), 2)
+            ])))
+        );
+        assert_eq!(fill.signature(), "fill((address,uint256[2])[])");
+        assert_eq!(fill.inputs[0].internal_type, Some("struct Order[]".to_string()));
+    }
+
+    #[test]
+    fn rejects_unknown_entries_and_types() {
+        assert!(Contract::load(r#"[{"type":"modifier","name":"onlyOwner"}]"#).is_err());
+        assert!(Contract::load(r#"[{"type":"function","name":"f","inputs":[{"name":"a","type":"uint7"}]}]"#).is_err());
+    }
+}
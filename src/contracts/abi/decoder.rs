@@ -0,0 +1,393 @@
+use std::str;
+
+use fixed_hash::clean_0x;
+use rustc_serialize::hex::FromHex;
+
+use error::{Error, ErrorKind};
+use types::{H160, U256};
+
+use super::types::{AbiValue, ParamType};
+
+/// How strictly encoded data is validated while decoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeMode {
+    /// Rejects anything the Solidity compiler would not have produced: dirty padding,
+    /// out-of-range integers, booleans other than 0/1, misaligned or backwards offsets,
+    /// invalid UTF-8 in strings and data that isn't a whole number of words.
+    Strict,
+    /// Only rejects data that can't be decoded at all, e.g. offsets or lengths pointing
+    /// past the end of the data.  Padding is ignored, values are truncated to their type,
+    /// and invalid UTF-8 is replaced.
+    Lenient
+}
+
+/// Decodes ABI-encoded data as the members of a tuple of the given types.
+pub fn decode(types: &[ParamType], data: &[u8], mode: DecodeMode) -> Result<Vec<AbiValue>, Error> {
+    if mode == DecodeMode::Strict && data.len() % 32 != 0 {
+        return Err(decode_error(format!("Data length {} is not a multiple of 32", data.len())));
+    }
+
+    for t in types {
+        t.validate()?;
+    }
+    Decoder { data, mode }.decode_tuple(types, 0)
+}
+
+/// Decodes hex-encoded data, with or without a `0x` prefix.
+pub fn decode_hex(types: &[ParamType], data: &str, mode: DecodeMode) -> Result<Vec<AbiValue>, Error> {
+    decode(types, &hex_to_bytes(data)?, mode)
+}
+
+/// Decodes the arguments of a call from its hex-encoded input, skipping the 4-byte method id.
+pub fn decode_call_input(
+    types: &[ParamType],
+    input: &str,
+    mode: DecodeMode
+) -> Result<Vec<AbiValue>, Error> {
+    let bytes = hex_to_bytes(input)?;
+    if bytes.len() < 4 {
+        return Err(decode_error(format!("Call input shorter than a method id: {}", input)));
+    }
+    decode(types, &bytes[4..], mode)
+}
+
+pub fn hex_to_bytes(data: &str) -> Result<Vec<u8>, Error> {
+    clean_0x(data).from_hex()
+        .map_err(|e| decode_error(format!("Invalid hex data ({}): {}", e, data)))
+}
+
+struct Decoder<'a> {
+    data: &'a [u8],
+    mode: DecodeMode
+}
+
+impl<'a> Decoder<'a> {
+    fn strict(&self) -> bool {
+        self.mode == DecodeMode::Strict
+    }
+
+    fn word(&self, pos: usize) -> Result<&'a [u8], Error> {
+        pos.checked_add(32)
+            .and_then(|end| self.data.get(pos..end))
+            .ok_or_else(|| decode_error(format!("Word at {} out of bounds", pos)))
+    }
+
+    fn usize_at(&self, pos: usize) -> Result<usize, Error> {
+        let word = self.word(pos)?;
+        let value = U256::from(word);
+        if value > U256::from(self.data.len()) {
+            Err(decode_error(format!("Offset or length {} at {} out of bounds", value, pos)))
+        } else {
+            Ok(value.low_u64() as usize)
+        }
+    }
+
+    /// Decodes a tuple whose head starts at `base`.  Offsets of dynamic members are relative
+    /// to `base`.
+    fn decode_tuple(&self, types: &[ParamType], base: usize) -> Result<Vec<AbiValue>, Error> {
+        let head_size = types.iter().map(|t| t.head_size()).sum::<usize>();
+        let mut cursor = base;
+        let mut values = Vec::with_capacity(types.len());
+        // Tails follow the head in member order, so each offset must be past the previous one.
+        let mut previous_offset = None;
+
+        for t in types {
+            if t.is_dynamic() {
+                let offset = self.usize_at(cursor)?;
+                let backwards = previous_offset.map_or(false, |previous| offset <= previous);
+                if self.strict() && (offset % 32 != 0 || offset < head_size || backwards) {
+                    return Err(decode_error(format!("Invalid offset {} at {}", offset, cursor)));
+                }
+                previous_offset = Some(offset);
+                values.push(self.decode_value(t, base + offset)?);
+            } else {
+                values.push(self.decode_value(t, cursor)?);
+            }
+            cursor += t.head_size();
+        }
+
+        Ok(values)
+    }
+
+    fn decode_value(&self, t: &ParamType, pos: usize) -> Result<AbiValue, Error> {
+        match *t {
+            ParamType::Address => {
+                let word = self.word(pos)?;
+                self.check_zeroes(&word[..12], pos)?;
+                Ok(AbiValue::Address(H160::from_slice(&word[12..])))
+            },
+            ParamType::Bool => {
+                let value = U256::from(self.word(pos)?);
+                if self.strict() && value > U256::one() {
+                    return Err(decode_error(format!("Invalid bool at {}", pos)));
+                }
+                Ok(AbiValue::Bool(!value.is_zero()))
+            },
+            ParamType::Uint(bits) => {
+                let value = U256::from(self.word(pos)?);
+                if value.bits() > bits {
+                    if self.strict() {
+                        return Err(decode_error(format!("uint{} out of range at {}", bits, pos)));
+                    }
+                    return Ok(AbiValue::Uint(truncate(value, bits)));
+                }
+                Ok(AbiValue::Uint(value))
+            },
+            ParamType::Int(bits) => {
+                let value = U256::from(self.word(pos)?);
+                let extended = sign_extend(value, bits);
+                if self.strict() && extended != value {
+                    return Err(decode_error(format!("int{} out of range at {}", bits, pos)));
+                }
+                Ok(AbiValue::Int(extended))
+            },
+            ParamType::FixedBytes(len) => {
+                let word = self.word(pos)?;
+                if len > 32 {
+                    return Err(decode_error(format!("Invalid type bytes{}", len)));
+                }
+                self.check_zeroes(&word[len..], pos)?;
+                Ok(AbiValue::FixedBytes(word[..len].to_vec()))
+            },
+            ParamType::Bytes => self.decode_bytes(pos).map(|b| AbiValue::Bytes(b.to_vec())),
+            ParamType::String => {
+                let bytes = self.decode_bytes(pos)?;
+                if self.strict() {
+                    str::from_utf8(bytes)
+                        .map(|s| AbiValue::String(s.to_string()))
+                        .map_err(|_| decode_error(format!("Invalid UTF-8 string at {}", pos)))
+                } else {
+                    Ok(AbiValue::String(String::from_utf8_lossy(bytes).into_owned()))
+                }
+            },
+            ParamType::Array(ref inner) => {
+                let len = self.usize_at(pos)?;
+                self.check_array_len(inner, len, pos + 32)?;
+                let types = vec![(**inner).clone(); len];
+                self.decode_tuple(&types, pos + 32).map(AbiValue::Array)
+            },
+            ParamType::FixedArray(ref inner, len) => {
+                self.check_array_len(inner, len, pos)?;
+                let types = vec![(**inner).clone(); len];
+                self.decode_tuple(&types, pos).map(AbiValue::FixedArray)
+            },
+            ParamType::Tuple(ref types) => self.decode_tuple(types, pos).map(AbiValue::Tuple)
+        }
+    }
+
+    fn decode_bytes(&self, pos: usize) -> Result<&'a [u8], Error> {
+        let len = self.usize_at(pos)?;
+        let start = pos + 32;
+        let bytes = start.checked_add(len)
+            .and_then(|end| self.data.get(start..end))
+            .ok_or_else(|| decode_error(format!("Bytes of length {} at {} out of bounds", len, pos)))?;
+
+        if self.strict() {
+            let padded_end = start + (len + 31) / 32 * 32;
+            let padding = self.data.get(start + len..padded_end)
+                .ok_or_else(|| decode_error(format!("Missing padding for bytes at {}", pos)))?;
+            self.check_zeroes(padding, pos)?;
+        }
+
+        Ok(bytes)
+    }
+
+    /// The heads of all elements must fit in the data, which also bounds the allocations
+    /// made for huge lengths.
+    fn check_array_len(&self, inner: &ParamType, len: usize, pos: usize) -> Result<(), Error> {
+        let heads_end = len.checked_mul(inner.head_size()).and_then(|size| size.checked_add(pos));
+        match heads_end {
+            Some(end) if end <= self.data.len() => Ok(()),
+            _ => Err(decode_error(format!("Array length {} at {} out of bounds", len, pos)))
+        }
+    }
+
+    fn check_zeroes(&self, bytes: &[u8], pos: usize) -> Result<(), Error> {
+        if self.strict() && bytes.iter().any(|b| *b != 0) {
+            Err(decode_error(format!("Dirty padding at {}", pos)))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+fn truncate(value: U256, bits: usize) -> U256 {
+    if bits >= 256 {
+        value
+    } else {
+        value & ((U256::one() << bits) - U256::one())
+    }
+}
+
+/// Sign-extends the lowest `bits` bits of `value` to a 256 bit two's complement word.
+pub(crate) fn sign_extend(value: U256, bits: usize) -> U256 {
+    if bits == 0 {
+        return U256::zero();
+    }
+    if bits >= 256 {
+        return value;
+    }
+
+    let low = truncate(value, bits);
+    if low.bit(bits - 1) {
+        low | !((U256::one() << bits) - U256::one())
+    } else {
+        low
+    }
+}
+
+fn decode_error(message: String) -> Error {
+    ErrorKind::Decoder(message).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use types::{H160, U256};
+    use contracts::abi::{encode, AbiValue, ParamType};
+    use super::{decode, decode_call_input, decode_hex, DecodeMode};
+
+    fn sample_types() -> Vec<ParamType> {
+        vec![
+            ParamType::Uint(256),
+            ParamType::Array(Box::new(ParamType::Uint(32))),
+            ParamType::FixedBytes(10),
+            ParamType::Bytes
+        ]
+    }
+
+    fn sample_values() -> Vec<AbiValue> {
+        vec![
+            AbiValue::Uint(U256::from(0x123)),
+            AbiValue::Array(vec![AbiValue::Uint(U256::from(0x456)), AbiValue::Uint(U256::from(0x789))]),
+            AbiValue::FixedBytes(b"1234567890".to_vec()),
+            AbiValue::Bytes(b"Hello, world!".to_vec())
+        ]
+    }
+
+    #[test]
+    fn round_trips_dynamic_values() {
+        let encoded = encode(&sample_values());
+        let decoded = decode(&sample_types(), &encoded, DecodeMode::Strict).unwrap();
+        assert_eq!(decoded, sample_values());
+    }
+
+    #[test]
+    fn round_trips_nested_tuples_and_strings() {
+        let types = vec![
+            ParamType::Tuple(vec![
+                ParamType::Address,
+                ParamType::String,
+                ParamType::FixedArray(Box::new(ParamType::Int(8)), 2)
+            ]),
+            ParamType::Array(Box::new(ParamType::String)),
+            ParamType::Bool
+        ];
+        let values = vec![
+            AbiValue::Tuple(vec![
+                AbiValue::Address(H160::from_str("8d12a197cb00d4747a1fe03395095ce2a5cc6819").unwrap()),
+                AbiValue::String("EtherDelta".to_string()),
+                AbiValue::FixedArray(vec![
+                    AbiValue::Int(!U256::zero()),
+                    AbiValue::Int(U256::from(127))
+                ])
+            ]),
+            AbiValue::Array(vec![AbiValue::String("one".to_string()), AbiValue::String("".to_string())]),
+            AbiValue::Bool(true)
+        ];
+
+        let encoded = encode(&values);
+        assert_eq!(decode(&types, &encoded, DecodeMode::Strict).unwrap(), values);
+    }
+
+    #[test]
+    fn decodes_call_input() {
+        let data = include_str!("../../../test_data/input_data_0x.txt");
+        let types = vec![
+            ParamType::Address, ParamType::Uint(256), ParamType::Address, ParamType::Uint(256),
+            ParamType::Uint(256), ParamType::Uint(256), ParamType::Address, ParamType::Uint(8),
+            ParamType::FixedBytes(32), ParamType::FixedBytes(32), ParamType::Uint(256)
+        ];
+
+        let values = decode_call_input(&types, data.trim(), DecodeMode::Strict).unwrap();
+
+        assert_eq!(
+            values[0].to_address(),
+            Some(H160::from_str("9214ec02cb71cba0ada6896b8da260736a67ab10").unwrap())
+        );
+        assert_eq!(values[7].to_uint(), Some(U256::from(28)));
+    }
+
+    #[test]
+    fn rejects_out_of_bounds_offsets() {
+        let mut encoded = encode(&sample_values());
+        encoded[63] = 0xff;
+
+        assert!(decode(&sample_types(), &encoded, DecodeMode::Strict).is_err());
+        assert!(decode(&sample_types(), &encoded, DecodeMode::Lenient).is_err());
+    }
+
+    #[test]
+    fn rejects_huge_array_lengths() {
+        let data = format!("{:064x}{}", 0x20, "f".repeat(64));
+        let types = vec![ParamType::Array(Box::new(ParamType::Uint(256)))];
+
+        assert!(decode_hex(&types, &data, DecodeMode::Lenient).is_err());
+    }
+
+    #[test]
+    fn strict_mode_rejects_dirty_values_that_lenient_mode_accepts() {
+        let dirty_address = format!("{}{}", "ff".repeat(12), "8d12a197cb00d4747a1fe03395095ce2a5cc6819");
+        let dirty_uint8 = format!("{:064x}", 0x1ff);
+        let dirty_bool = format!("{:064x}", 2);
+        let types = vec![ParamType::Address, ParamType::Uint(8), ParamType::Bool];
+        let data = format!("{}{}{}", dirty_address, dirty_uint8, dirty_bool);
+
+        assert!(decode_hex(&types, &data, DecodeMode::Strict).is_err());
+
+        let values = decode_hex(&types, &data, DecodeMode::Lenient).unwrap();
+        assert_eq!(values[1], AbiValue::Uint(U256::from(0xff)));
+        assert_eq!(values[2], AbiValue::Bool(true));
+    }
+
+    #[test]
+    fn strict_mode_rejects_trailing_partial_words() {
+        let data = format!("{:064x}ff", 1);
+        let types = vec![ParamType::Uint(256)];
+
+        assert!(decode_hex(&types, &data, DecodeMode::Strict).is_err());
+        assert!(decode_hex(&types, &data, DecodeMode::Lenient).is_ok());
+    }
+
+    #[test]
+    fn strict_mode_rejects_backwards_offsets() {
+        let types = vec![ParamType::Bytes, ParamType::Bytes];
+        // Both offsets point at the same tail, which the compiler never produces.
+        let data = format!("{:064x}{:064x}{:064x}ab{}", 0x40, 0x40, 1, "0".repeat(62));
+
+        assert!(decode_hex(&types, &data, DecodeMode::Strict).is_err());
+        assert_eq!(
+            decode_hex(&types, &data, DecodeMode::Lenient).unwrap(),
+            vec![AbiValue::Bytes(vec![0xab]), AbiValue::Bytes(vec![0xab])]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_type_sizes() {
+        let data = format!("{:064x}", 1);
+        for t in &[ParamType::Int(0), ParamType::Uint(7), ParamType::Uint(264), ParamType::FixedBytes(33)] {
+            assert!(decode_hex(::std::slice::from_ref(t), &data, DecodeMode::Lenient).is_err(), "{:?}", t);
+        }
+        let nested = ParamType::Tuple(vec![ParamType::Array(Box::new(ParamType::Int(0)))]);
+        assert!(decode_hex(&[nested], &data, DecodeMode::Lenient).is_err());
+    }
+
+    #[test]
+    fn sign_extends_negative_ints() {
+        let data = format!("{:064x}", 0xff);
+        let values = decode_hex(&[ParamType::Int(8)], &data, DecodeMode::Lenient).unwrap();
+        assert_eq!(values[0], AbiValue::Int(!U256::zero()));
+        assert!(decode_hex(&[ParamType::Int(8)], &data, DecodeMode::Strict).is_err());
+    }
+}
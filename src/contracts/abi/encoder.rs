@@ -0,0 +1,169 @@
+use rustc_serialize::hex::ToHex;
+
+use types::U256;
+
+use super::types::AbiValue;
+
+/// ABI-encodes a list of values as the members of a tuple, e.g. function arguments.
+pub fn encode(values: &[AbiValue]) -> Vec<u8> {
+    let head_size = values.iter()
+        .map(|v| if v.is_dynamic() { 32 } else { static_size(v) })
+        .sum::<usize>();
+
+    let mut head = Vec::with_capacity(head_size);
+    let mut tail = Vec::new();
+
+    for value in values {
+        if value.is_dynamic() {
+            head.extend_from_slice(&encode_word(U256::from(head_size + tail.len())));
+            tail.extend(encode_value(value));
+        } else {
+            head.extend(encode_value(value));
+        }
+    }
+
+    head.extend(tail);
+    head
+}
+
+/// ABI-encodes values and returns them as a `0x`-prefixed hex string.
+pub fn encode_hex(values: &[AbiValue]) -> String {
+    format!("0x{}", encode(values).to_hex())
+}
+
+fn encode_value(value: &AbiValue) -> Vec<u8> {
+    match *value {
+        AbiValue::Address(ref a) => {
+            let mut word = [0u8; 32];
+            word[12..].copy_from_slice(a);
+            word.to_vec()
+        },
+        AbiValue::Bool(b) => encode_word(if b { U256::one() } else { U256::zero() }).to_vec(),
+        AbiValue::Int(n) | AbiValue::Uint(n) => encode_word(n).to_vec(),
+        AbiValue::FixedBytes(ref b) => pad_right(b),
+        AbiValue::Bytes(ref b) => encode_bytes(b),
+        AbiValue::String(ref s) => encode_bytes(s.as_bytes()),
+        AbiValue::Array(ref vs) => {
+            let mut out = encode_word(U256::from(vs.len())).to_vec();
+            out.extend(encode(vs));
+            out
+        },
+        AbiValue::FixedArray(ref vs) | AbiValue::Tuple(ref vs) => encode(vs)
+    }
+}
+
+fn static_size(value: &AbiValue) -> usize {
+    match *value {
+        AbiValue::FixedArray(ref vs) | AbiValue::Tuple(ref vs) => vs.iter().map(static_size).sum(),
+        _ => 32
+    }
+}
+
+fn encode_word(n: U256) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    n.to_big_endian(&mut word);
+    word
+}
+
+fn encode_bytes(b: &[u8]) -> Vec<u8> {
+    let mut out = encode_word(U256::from(b.len())).to_vec();
+    out.extend(pad_right(b));
+    out
+}
+
+fn pad_right(b: &[u8]) -> Vec<u8> {
+    let mut out = b.to_vec();
+    let padded_len = (b.len() + 31) / 32 * 32;
+    out.resize(padded_len, 0);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use types::{H160, U256};
+    use contracts::abi::AbiValue;
+    use super::encode_hex;
+
+    #[test]
+    fn encodes_static_values() {
+        let encoded = encode_hex(&[
+            AbiValue::Address(H160::from_str("8d12a197cb00d4747a1fe03395095ce2a5cc6819").unwrap()),
+            AbiValue::Uint(U256::from(69)),
+            AbiValue::Bool(true)
+        ]);
+
+        assert_eq!(
+            encoded,
+            "0x0000000000000000000000008d12a197cb00d4747a1fe03395095ce2a5cc6819\
+             0000000000000000000000000000000000000000000000000000000000000045\
+             0000000000000000000000000000000000000000000000000000000000000001"
+        );
+    }
+
+    #[test]
+    fn encodes_dynamic_values() {
+        // Example from the Solidity ABI specification: f(uint,uint32[],bytes10,bytes)
+        // called with (0x123, [0x456, 0x789], "1234567890", "Hello, world!").
+        let encoded = encode_hex(&[
+            AbiValue::Uint(U256::from(0x123)),
+            AbiValue::Array(vec![AbiValue::Uint(U256::from(0x456)), AbiValue::Uint(U256::from(0x789))]),
+            AbiValue::FixedBytes(b"1234567890".to_vec()),
+            AbiValue::Bytes(b"Hello, world!".to_vec())
+        ]);
+
+        assert_eq!(
+            encoded,
+            "0x0000000000000000000000000000000000000000000000000000000000000123\
+             0000000000000000000000000000000000000000000000000000000000000080\
+             3132333435363738393000000000000000000000000000000000000000000000\
+             00000000000000000000000000000000000000000000000000000000000000e0\
+             0000000000000000000000000000000000000000000000000000000000000002\
+             0000000000000000000000000000000000000000000000000000000000000456\
+             0000000000000000000000000000000000000000000000000000000000000789\
+             000000000000000000000000000000000000000000000000000000000000000d\
+             48656c6c6f2c20776f726c642100000000000000000000000000000000000000"
+        );
+    }
+
+    #[test]
+    fn encodes_nested_dynamic_arrays() {
+        // Example from the Solidity ABI specification: g(uint[][],string[])
+        // called with ([[1, 2], [3]], ["one", "two", "three"]).
+        let uint = |n: u64| AbiValue::Uint(U256::from(n));
+        let string = |s: &str| AbiValue::String(s.to_string());
+
+        let encoded = encode_hex(&[
+            AbiValue::Array(vec![
+                AbiValue::Array(vec![uint(1), uint(2)]),
+                AbiValue::Array(vec![uint(3)])
+            ]),
+            AbiValue::Array(vec![string("one"), string("two"), string("three")])
+        ]);
+
+        assert_eq!(
+            encoded,
+            "0x0000000000000000000000000000000000000000000000000000000000000040\
+             0000000000000000000000000000000000000000000000000000000000000140\
+             0000000000000000000000000000000000000000000000000000000000000002\
+             0000000000000000000000000000000000000000000000000000000000000040\
+             00000000000000000000000000000000000000000000000000000000000000a0\
+             0000000000000000000000000000000000000000000000000000000000000002\
+             0000000000000000000000000000000000000000000000000000000000000001\
+             0000000000000000000000000000000000000000000000000000000000000002\
+             0000000000000000000000000000000000000000000000000000000000000001\
+             0000000000000000000000000000000000000000000000000000000000000003\
+             0000000000000000000000000000000000000000000000000000000000000003\
+             0000000000000000000000000000000000000000000000000000000000000060\
+             00000000000000000000000000000000000000000000000000000000000000a0\
+             00000000000000000000000000000000000000000000000000000000000000e0\
+             0000000000000000000000000000000000000000000000000000000000000003\
+             6f6e650000000000000000000000000000000000000000000000000000000000\
+             0000000000000000000000000000000000000000000000000000000000000003\
+             74776f0000000000000000000000000000000000000000000000000000000000\
+             0000000000000000000000000000000000000000000000000000000000000005\
+             7468726565000000000000000000000000000000000000000000000000000000"
+        );
+    }
+}
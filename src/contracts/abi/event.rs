@@ -0,0 +1,230 @@
+use rustc_serialize::hex::ToHex;
+
+use error::{Error, ErrorKind};
+use objects::Log;
+use types::{H160, U256};
+
+use super::contract::{Contract, Event};
+use super::decoder::{decode, hex_to_bytes, DecodeMode};
+use super::types::{AbiValue, ParamType};
+
+/// A decoded event parameter.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogParam {
+    pub name: String,
+    pub value: AbiValue,
+    pub indexed: bool,
+    /// Indexed strings, bytes, arrays and tuples are stored as the keccak hash of their
+    /// encoding, which can't be reversed.  Their `value` is the raw 32 byte topic.
+    pub hashed: bool
+}
+
+/// A log decoded against an event definition, with its parameters in declaration order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DecodedLog {
+    pub event: String,
+    pub params: Vec<LogParam>
+}
+
+impl DecodedLog {
+    pub fn get(&self, name: &str) -> Option<&AbiValue> {
+        self.params.iter().find(|p| p.name == name).map(|p| &p.value)
+    }
+
+    pub fn address(&self, name: &str) -> Result<H160, Error> {
+        self.typed(name, AbiValue::to_address)
+    }
+
+    pub fn uint(&self, name: &str) -> Result<U256, Error> {
+        self.typed(name, AbiValue::to_uint)
+    }
+
+    pub fn boolean(&self, name: &str) -> Result<bool, Error> {
+        self.typed(name, AbiValue::to_bool)
+    }
+
+    /// Hex-encoded (no `0x`) value of a `bytes<M>` or `bytes` parameter.
+    pub fn hex(&self, name: &str) -> Result<String, Error> {
+        self.typed(name, |v| v.to_bytes().map(|b| b.to_hex()))
+    }
+
+    fn typed<T, F>(&self, name: &str, f: F) -> Result<T, Error>
+        where F: Fn(&AbiValue) -> Option<T>
+    {
+        self.get(name)
+            .and_then(f)
+            .ok_or_else(|| decode_error(format!("{} has no parameter {} of the expected type", self.event, name)))
+    }
+}
+
+impl Event {
+    /// Decodes a log emitted by this event: indexed parameters are read from `topics[1..]`
+    /// (`topics[0..]` for anonymous events), the others from `data`.
+    pub fn decode_log(&self, log: &Log, mode: DecodeMode) -> Result<DecodedLog, Error> {
+        let topics = if self.anonymous {
+            &log.topics[..]
+        } else {
+            match log.topics.first() {
+                Some(topic) if *topic == self.topic() => &log.topics[1..],
+                _ => return Err(decode_error(format!("Log is not a {} event: {:?}", self.name, log.topics)))
+            }
+        };
+
+        let indexed = self.inputs.iter().filter(|p| p.indexed).collect::<Vec<_>>();
+        if topics.len() != indexed.len() {
+            return Err(decode_error(format!(
+                "{} expects {} indexed parameters, log has {} topics", self.name, indexed.len(), topics.len()
+            )));
+        }
+
+        let mut indexed_values = Vec::with_capacity(indexed.len());
+        for (param, topic) in indexed.iter().zip(topics) {
+            if is_hashed(&param.kind) {
+                indexed_values.push((AbiValue::FixedBytes(topic.to_vec()), true));
+            } else {
+                let value = decode(::std::slice::from_ref(&param.kind), topic, mode)?.remove(0);
+                indexed_values.push((value, false));
+            }
+        }
+
+        let data_types = self.inputs.iter()
+            .filter(|p| !p.indexed)
+            .map(|p| p.kind.clone())
+            .collect::<Vec<ParamType>>();
+        let data = hex_to_bytes(&log.data)?;
+        let static_size = data_types.iter().map(|t| t.head_size()).sum::<usize>();
+
+        // Trailing data can't be detected after dynamic values, which may be laid out freely.
+        if mode == DecodeMode::Strict && data_types.iter().all(|t| !t.is_dynamic()) && data.len() != static_size {
+            return Err(decode_error(format!("Unexpected data length for {}: {}", self.name, data.len())));
+        }
+
+        let data_values = decode(&data_types, &data, mode)?;
+
+        let mut indexed_values = indexed_values.into_iter();
+        let mut data_values = data_values.into_iter();
+
+        let params = self.inputs.iter()
+            .map(|p| {
+                let (value, hashed) = if p.indexed {
+                    indexed_values.next().expect("indexed values match indexed params")
+                } else {
+                    (data_values.next().expect("data values match data params"), false)
+                };
+                LogParam { name: p.name.clone(), value, indexed: p.indexed, hashed }
+            })
+            .collect();
+
+        Ok(DecodedLog { event: self.name.clone(), params })
+    }
+}
+
+impl Contract {
+    /// Decodes a log against the named event, in strict mode.
+    pub fn decode_log(&self, name: &str, log: &Log) -> Result<DecodedLog, Error> {
+        self.event(name)
+            .ok_or_else(|| decode_error(format!("Unknown event: {}", name)))
+            .and_then(|event| event.decode_log(log, DecodeMode::Strict))
+    }
+}
+
+/// Reference types are hashed when indexed.
+fn is_hashed(kind: &ParamType) -> bool {
+    matches!(
+        *kind,
+        ParamType::Bytes | ParamType::String | ParamType::Array(_) | ParamType::FixedArray(..) | ParamType::Tuple(_)
+    )
+}
+
+fn decode_error(message: String) -> Error {
+    ErrorKind::Decoder(message).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use contracts::abi::{keccak256, AbiValue, DecodeMode, Event};
+    use objects::fixtures::log;
+    use types::{H160, H256, U256};
+
+    fn address_topic(address: &str) -> H256 {
+        H256::from_str(&format!("{:0>64}", address)).unwrap()
+    }
+
+    #[test]
+    fn decodes_indexed_and_data_params() {
+        let transfer = "event Transfer(address indexed from, address indexed to, uint256 value)"
+            .parse::<Event>()
+            .unwrap();
+        let decoded = transfer.decode_log(&log(
+            H160::zero(),
+            vec![
+                transfer.topic(),
+                address_topic("8d12a197cb00d4747a1fe03395095ce2a5cc6819"),
+                address_topic("f43b43869dd95e53923b30a79902915c1faa47d3")
+            ],
+            "0x00000000000000000000000000000000000000000000000000000000000003e8"
+        ), DecodeMode::Strict).unwrap();
+
+        assert_eq!(decoded.event, "Transfer");
+        assert_eq!(decoded.address("from").unwrap(), H160::from_str("8d12a197cb00d4747a1fe03395095ce2a5cc6819").unwrap());
+        assert_eq!(decoded.address("to").unwrap(), H160::from_str("f43b43869dd95e53923b30a79902915c1faa47d3").unwrap());
+        assert_eq!(decoded.uint("value").unwrap(), U256::from(1000));
+        assert!(decoded.params[0].indexed && !decoded.params[2].indexed);
+    }
+
+    #[test]
+    fn keeps_hashed_dynamic_params_opaque() {
+        let event = "event Named(string indexed name, uint8 kind)".parse::<Event>().unwrap();
+        let name_hash = keccak256(b"zrx");
+        let decoded = event.decode_log(&log(
+            H160::zero(),
+            vec![event.topic(), name_hash],
+            "0x0000000000000000000000000000000000000000000000000000000000000002"
+        ), DecodeMode::Strict).unwrap();
+
+        assert!(decoded.params[0].hashed);
+        assert_eq!(decoded.get("name"), Some(&AbiValue::FixedBytes(name_hash.to_vec())));
+        assert_eq!(decoded.uint("kind").unwrap(), U256::from(2));
+    }
+
+    #[test]
+    fn decodes_anonymous_events() {
+        let event = "event Ping(address indexed who, uint256 n) anonymous".parse::<Event>().unwrap();
+        let decoded = event.decode_log(&log(
+            H160::zero(),
+            vec![address_topic("8d12a197cb00d4747a1fe03395095ce2a5cc6819")],
+            "0x0000000000000000000000000000000000000000000000000000000000000007"
+        ), DecodeMode::Strict).unwrap();
+
+        assert_eq!(decoded.uint("n").unwrap(), U256::from(7));
+    }
+
+    #[test]
+    fn rejects_mismatched_logs() {
+        let transfer = "event Transfer(address indexed from, address indexed to, uint256 value)"
+            .parse::<Event>()
+            .unwrap();
+        let value = "0x00000000000000000000000000000000000000000000000000000000000003e8";
+
+        // Wrong topic0, missing indexed topic, and ERC721-style all-indexed layout.
+        assert!(transfer.decode_log(&log(H160::zero(), vec![H256::zero()], value), DecodeMode::Strict).is_err());
+        assert!(transfer.decode_log(
+            &log(H160::zero(), vec![transfer.topic(), H256::zero()], value),
+            DecodeMode::Strict
+        ).is_err());
+        assert!(transfer.decode_log(
+            &log(H160::zero(), vec![transfer.topic(), H256::zero(), H256::zero(), H256::zero()], "0x"),
+            DecodeMode::Strict
+        ).is_err());
+        assert!(transfer.decode_log(
+            &log(
+                H160::zero(),
+                vec![transfer.topic(), H256::zero(), H256::zero()],
+                &format!("{}{}", value, &value[2..])
+            ),
+            DecodeMode::Strict
+        ).is_err());
+    }
+}
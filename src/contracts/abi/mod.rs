@@ -0,0 +1,30 @@
+//! Solidity ABI encoding and decoding of typed values, covering all static and dynamic types.
+//! Contract decoders should build on `decode_call_input` and `decode_hex` rather than splitting
+//! data into words by hand.
+
+mod bindings;
+mod contract;
+mod decoder;
+mod encoder;
+mod event;
+mod registry;
+mod signature;
+mod types;
+
+pub use self::bindings::{take_value, FromAbiValue};
+pub use self::contract::{
+    keccak256,
+    AbiError,
+    Constructor,
+    Contract,
+    Event,
+    EventParam,
+    Function,
+    Param,
+    StateMutability
+};
+pub use self::decoder::{decode, decode_call_input, decode_hex, hex_to_bytes, DecodeMode};
+pub use self::encoder::{encode, encode_hex};
+pub use self::event::{DecodedLog, LogParam};
+pub use self::registry::{DecodedCall, EventRegistry, LoadReport, SelectorRegistry};
+pub use self::types::{AbiValue, ParamType};
@@ -0,0 +1,549 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use rustc_serialize::hex::ToHex;
+use serde_json;
+
+use contracts::{ContractFunction, NamedFunction};
+use contracts::etherdelta::constants::ETHERDELTA_ABI;
+use error::{Error, ErrorKind};
+use objects::{Log, ParityTrace, Transaction};
+use types::H256;
+
+use super::contract::{Contract, Event, Function};
+use super::decoder::{decode, hex_to_bytes, DecodeMode};
+use super::event::DecodedLog;
+use super::encoder::encode;
+use super::types::AbiValue;
+
+/// The outcome of loading a list of signatures: how many were added, and the entries that
+/// were skipped with the reason why.  Entries already registered are neither.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LoadReport {
+    pub loaded: usize,
+    pub rejected: Vec<(String, String)>
+}
+
+/// A call decoded against one of the candidate signatures of its selector.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedCall {
+    pub function: Function,
+    pub values: Vec<AbiValue>
+}
+
+impl NamedFunction for DecodedCall {
+    fn get_function(&self) -> ContractFunction {
+        if self.function.is_constant() {
+            ContractFunction::Immutable(self.function.signature())
+        } else {
+            ContractFunction::Mutable(self.function.signature())
+        }
+    }
+}
+
+/// Maps 4-byte selectors to the signatures that hash to them.  Selectors are only 32 bits
+/// long, so a selector may have several candidates, e.g. from a 4byte.directory dump; they
+/// are tried in the order they were added.
+#[derive(Debug, Clone, Default)]
+pub struct SelectorRegistry {
+    functions: HashMap<[u8; 4], Vec<Function>>
+}
+
+impl SelectorRegistry {
+    pub fn new() -> SelectorRegistry {
+        SelectorRegistry::default()
+    }
+
+    /// A registry containing all functions of a contract.
+    pub fn from_contract(contract: &Contract) -> SelectorRegistry {
+        let mut registry = SelectorRegistry::new();
+        registry.add_contract(contract);
+        registry
+    }
+
+    pub fn add_contract(&mut self, contract: &Contract) {
+        for function in &contract.functions {
+            self.insert(function.clone());
+        }
+    }
+
+    /// Adds a candidate, unless a function with the same signature is already registered.
+    /// Returns whether it was added.
+    pub fn insert(&mut self, function: Function) -> bool {
+        let candidates = self.functions.entry(function.selector()).or_default();
+        let added = candidates.iter().all(|f| f.signature() != function.signature());
+        if added {
+            candidates.push(function);
+        }
+        added
+    }
+
+    /// Parses and adds a human-readable signature, e.g. `transfer(address,uint256)`.
+    pub fn insert_signature(&mut self, signature: &str) -> Result<(), Error> {
+        let function = signature.parse::<Function>()?;
+        self.insert(function);
+        Ok(())
+    }
+
+    /// Loads signatures in any of the formats accepted by `load_json` and `load_text`.
+    pub fn load_file<P: AsRef<Path>>(&mut self, path: P) -> Result<LoadReport, Error> {
+        let mut contents = String::new();
+        File::open(path)?.read_to_string(&mut contents)?;
+
+        if contents.trim_start().starts_with('{') {
+            self.load_json(&contents)
+        } else {
+            self.load_text(&contents)
+        }
+    }
+
+    /// Loads either a `{"0xa9059cbb": ["transfer(address,uint256)", ...], ...}` map, or a
+    /// 4byte.directory API response (`{"results": [{"hex_signature": ..., "text_signature":
+    /// ...}]}`).  Signatures that don't parse or don't hash to their selector are skipped and
+    /// reported; only JSON that isn't in either format fails the load.
+    pub fn load_json(&mut self, json: &str) -> Result<LoadReport, Error> {
+        let mut entries = match serde_json::from_str::<FourByteResponse>(json) {
+            Ok(response) => response.results.into_iter()
+                .map(|r| (r.hex_signature, vec![r.text_signature]))
+                .collect::<Vec<(String, Vec<String>)>>(),
+            Err(_) => serde_json::from_str::<HashMap<String, Vec<String>>>(json)?
+                .into_iter()
+                .collect()
+        };
+        // Maps have no order; sorting keeps the candidates' order and the report stable.
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut report = LoadReport::default();
+        for (selector, signatures) in entries {
+            for signature in signatures {
+                match parse_with_selector(&signature, Some(&selector)) {
+                    Ok(function) => report.loaded += self.insert(function) as usize,
+                    Err(e) => report.rejected.push((signature, e.to_string()))
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Loads one signature per line, optionally preceded by its selector
+    /// (`0xa9059cbb transfer(address,uint256)`).  Blank lines and `#` comments are skipped,
+    /// and lines that don't parse, or whose signature doesn't hash to their selector, are
+    /// reported.
+    pub fn load_text(&mut self, text: &str) -> Result<LoadReport, Error> {
+        let mut report = LoadReport::default();
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (selector, signature) = match line.find(|c: char| c.is_whitespace() || c == ',') {
+                Some(i) if line.starts_with("0x") && !line[..i].contains('(') => {
+                    (Some(&line[..i]), line[i + 1..].trim())
+                },
+                _ => (None, line)
+            };
+            match parse_with_selector(signature, selector) {
+                Ok(function) => report.loaded += self.insert(function) as usize,
+                Err(e) => report.rejected.push((line.to_string(), e.to_string()))
+            }
+        }
+
+        Ok(report)
+    }
+
+    pub fn candidates(&self, selector: &[u8; 4]) -> &[Function] {
+        self.functions.get(selector).map(|f| &f[..]).unwrap_or(&[])
+    }
+
+    pub fn len(&self) -> usize {
+        self.functions.values().map(|f| f.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.functions.is_empty()
+    }
+
+    /// Best-effort decoding of hex calldata.  Candidates whose strict decoding re-encodes to
+    /// exactly the input are preferred; failing that, the first candidate that decodes
+    /// strictly at all is used.
+    pub fn decode_input(&self, input: &str) -> Result<DecodedCall, Error> {
+        let bytes = hex_to_bytes(input)?;
+        if bytes.len() < 4 {
+            return Err(ErrorKind::Decoder(format!("Call input shorter than a method id: {}", input)).into());
+        }
+
+        let selector = [bytes[0], bytes[1], bytes[2], bytes[3]];
+        let args = &bytes[4..];
+
+        let mut decoded = self.candidates(&selector).iter()
+            .filter_map(|f| {
+                let types = f.inputs.iter().map(|p| p.kind.clone()).collect::<Vec<_>>();
+                decode(&types, args, DecodeMode::Strict)
+                    .ok()
+                    .map(|values| DecodedCall { function: f.clone(), values })
+            })
+            .collect::<Vec<DecodedCall>>();
+
+        if decoded.is_empty() {
+            return Err(ErrorKind::Decoder(
+                format!("No known signature decodes input with selector 0x{}", selector.to_hex())
+            ).into());
+        }
+
+        let canonical = decoded.iter().position(|call| encode(&call.values) == args);
+        Ok(decoded.swap_remove(canonical.unwrap_or(0)))
+    }
+
+    pub fn decode_transaction(&self, tx: &Transaction) -> Result<DecodedCall, Error> {
+        self.decode_input(&tx.input)
+    }
+
+    pub fn decode_trace(&self, trace: &ParityTrace) -> Result<DecodedCall, Error> {
+        self.decode_input(&trace.action.input)
+    }
+}
+
+/// Events of the token standards the registry is seeded with.  WETH's `Transfer` and
+/// `Approval` have the ERC20 layout and are covered by the ERC20 entries.
+const STANDARD_EVENTS: &[&str] = &[
+    // ERC20
+    "event Transfer(address indexed from, address indexed to, uint256 value)",
+    "event Approval(address indexed owner, address indexed spender, uint256 value)",
+    // ERC721
+    "event Transfer(address indexed from, address indexed to, uint256 indexed tokenId)",
+    "event Approval(address indexed owner, address indexed approved, uint256 indexed tokenId)",
+    "event ApprovalForAll(address indexed owner, address indexed operator, bool approved)",
+    // ERC1155
+    "event TransferSingle(address indexed operator, address indexed from, address indexed to, uint256 id, uint256 value)",
+    "event TransferBatch(address indexed operator, address indexed from, address indexed to, uint256[] ids, uint256[] values)",
+    "event URI(string value, uint256 indexed id)",
+    // WETH9
+    "event Deposit(address indexed dst, uint256 wad)",
+    "event Withdrawal(address indexed src, uint256 wad)"
+];
+
+/// Maps topic0 hashes to the events that emit them.  Several events may share a topic but
+/// differ in which parameters are indexed, such as ERC20's and ERC721's `Transfer`; the
+/// number of topics of a log decides between them.
+#[derive(Debug, Clone, Default)]
+pub struct EventRegistry {
+    events: HashMap<H256, Vec<Event>>
+}
+
+impl EventRegistry {
+    pub fn new() -> EventRegistry {
+        EventRegistry::default()
+    }
+
+    /// A registry seeded with the ERC20, ERC721, ERC1155, WETH and EtherDelta events.
+    pub fn standard() -> EventRegistry {
+        let mut registry = EventRegistry::new();
+
+        for signature in STANDARD_EVENTS {
+            registry.insert_signature(signature).expect("Invalid standard event signature");
+        }
+        registry.add_contract(&ETHERDELTA_ABI);
+
+        registry
+    }
+
+    pub fn add_contract(&mut self, contract: &Contract) {
+        for event in contract.events.iter().filter(|e| !e.anonymous) {
+            self.insert(event.clone());
+        }
+    }
+
+    /// Adds a candidate, unless one with the same parameter layout is already registered.
+    /// Anonymous events have no topic0 and are ignored.  Returns whether it was added.
+    pub fn insert(&mut self, event: Event) -> bool {
+        if event.anonymous {
+            return false;
+        }
+
+        let layout = |e: &Event| e.inputs.iter().map(|p| (p.kind.clone(), p.indexed)).collect::<Vec<_>>();
+        let candidates = self.events.entry(event.topic()).or_default();
+        let added = candidates.iter().all(|e| e.name != event.name || layout(e) != layout(&event));
+        if added {
+            candidates.push(event);
+        }
+        added
+    }
+
+    /// Parses and adds a human-readable signature, e.g.
+    /// `event Transfer(address indexed from, address indexed to, uint256 value)`.
+    pub fn insert_signature(&mut self, signature: &str) -> Result<(), Error> {
+        let event = signature.parse::<Event>()?;
+        self.insert(event);
+        Ok(())
+    }
+
+    /// Loads signatures from a JSON ABI, a `{"0xddf252ad...": ["event Transfer(...)", ...]}`
+    /// map, or plain text with one signature per line.
+    pub fn load_file<P: AsRef<Path>>(&mut self, path: P) -> Result<LoadReport, Error> {
+        let mut contents = String::new();
+        File::open(path)?.read_to_string(&mut contents)?;
+
+        match contents.trim_start().chars().next() {
+            Some('[') => {
+                let contract = Contract::load(&contents)?;
+                let before = self.len();
+                self.add_contract(&contract);
+                Ok(LoadReport { loaded: self.len() - before, rejected: vec![] })
+            },
+            Some('{') => self.load_json(&contents),
+            _ => self.load_text(&contents)
+        }
+    }
+
+    /// Loads a `{"0xddf252ad...": ["event Transfer(...)", ...]}` map.  Signatures that don't
+    /// parse or don't hash to their topic are skipped and reported.
+    pub fn load_json(&mut self, json: &str) -> Result<LoadReport, Error> {
+        let mut entries = serde_json::from_str::<HashMap<H256, Vec<String>>>(json)?
+            .into_iter()
+            .collect::<Vec<_>>();
+        entries.sort_by_key(|&(topic, _)| topic);
+
+        let mut report = LoadReport::default();
+        for (topic, signatures) in entries {
+            for signature in signatures {
+                let result = signature.parse::<Event>().and_then(|event| {
+                    if event.topic() == topic {
+                        Ok(event)
+                    } else {
+                        Err(ErrorKind::Abi(format!("{} does not hash to topic {:?}", signature, topic)).into())
+                    }
+                });
+                match result {
+                    Ok(event) => report.loaded += self.insert(event) as usize,
+                    Err(e) => report.rejected.push((signature, e.to_string()))
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Loads one signature per line.  Blank lines and `#` comments are skipped, and lines
+    /// that don't parse are reported.
+    pub fn load_text(&mut self, text: &str) -> Result<LoadReport, Error> {
+        let mut report = LoadReport::default();
+        for line in text.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#')) {
+            match line.parse::<Event>() {
+                Ok(event) => report.loaded += self.insert(event) as usize,
+                Err(e) => report.rejected.push((line.to_string(), e.to_string()))
+            }
+        }
+
+        Ok(report)
+    }
+
+    pub fn candidates(&self, topic: &H256) -> &[Event] {
+        self.events.get(topic).map(|e| &e[..]).unwrap_or(&[])
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.values().map(|e| e.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Decodes a log with the first candidate for its topic0 that has as many indexed
+    /// parameters as the log has topics and decodes strictly.
+    pub fn decode_log(&self, log: &Log) -> Result<DecodedLog, Error> {
+        let topic = log.topics.first()
+            .ok_or_else(|| Error::from(ErrorKind::Decoder("Log has no topics".to_string())))?;
+
+        self.candidates(topic).iter()
+            .filter(|e| e.inputs.iter().filter(|p| p.indexed).count() + 1 == log.topics.len())
+            .filter_map(|e| e.decode_log(log, DecodeMode::Strict).ok())
+            .next()
+            .ok_or_else(|| ErrorKind::Decoder(format!("No known event decodes log with topic {:?}", topic)).into())
+    }
+}
+
+/// Parses a function signature, checking that it hashes to `selector` if one is given.
+fn parse_with_selector(signature: &str, selector: Option<&str>) -> Result<Function, Error> {
+    let function = signature.parse::<Function>()?;
+    match selector {
+        Some(selector) if function.method_id() != selector.to_lowercase() => {
+            Err(ErrorKind::Abi(format!("{} does not hash to selector {}", signature, selector)).into())
+        },
+        _ => Ok(function)
+    }
+}
+
+#[derive(Deserialize)]
+struct FourByteResponse {
+    results: Vec<FourByteSignature>
+}
+
+#[derive(Deserialize)]
+struct FourByteSignature {
+    hex_signature: String,
+    text_signature: String
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use contracts::{ContractFunction, NamedFunction};
+    use contracts::abi::{AbiValue, EventRegistry, SelectorRegistry};
+    use contracts::etherdelta::constants::{ETHERDELTA_ABI, ETHERDELTA_WITHDRAW_TOPIC};
+    use objects::fixtures::{log, proxy_traces};
+    use types::{H160, H256, U256};
+
+    const TRANSFER_TOPIC: &str = "ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef";
+
+    fn word(n: u64) -> H256 {
+        H256::from_str(&format!("{:064x}", n)).unwrap()
+    }
+
+    const TRANSFER_INPUT: &str = "0xa9059cbb\
+        000000000000000000000000461680c41786ec889ac9de7f5e89a2f4e0fcb156\
+        00000000000000000000000000000000000000000000000000000000000003e8";
+
+    #[test]
+    fn loads_4byte_json() {
+        let mut registry = SelectorRegistry::new();
+        registry.load_json(include_str!("../../../test_data/selectors.json")).unwrap();
+
+        let mut api = SelectorRegistry::new();
+        api.load_json(r#"{"count":1,"results":[
+            {"id":145,"hex_signature":"0xa9059cbb","text_signature":"transfer(address,uint256)"}
+        ]}"#).unwrap();
+
+        assert_eq!(registry.candidates(&[0xa9, 0x05, 0x9c, 0xbb]).len(), 2);
+        assert_eq!(api.len(), 1);
+
+        let report = registry.load_json(r#"{
+            "0x12345678": ["transfer(address,uint256)"],
+            "0x18160ddd": ["totalSupply(", "totalSupply()"],
+            "0xa9059cbb": ["transfer(address,uint256)"]
+        }"#).unwrap();
+        assert_eq!(report.loaded, 1);
+        assert_eq!(
+            report.rejected.iter().map(|r| &r.0[..]).collect::<Vec<_>>(),
+            vec!["transfer(address,uint256)", "totalSupply("]
+        );
+        assert!(report.rejected[0].1.contains("does not hash to selector 0x12345678"));
+        assert_eq!(registry.candidates(&[0x18, 0x16, 0x0d, 0xdd]).len(), 1);
+        assert!(registry.load_json("[]").is_err());
+    }
+
+    #[test]
+    fn loads_plain_text() {
+        let mut registry = SelectorRegistry::new();
+        let report = registry.load_text("# ERC20\n\
+                                         transfer(address,uint256)\n\
+                                         0x095ea7b3 approve(address,uint256\n\
+                                         \n\
+                                         0x70a08231,balanceOf(address)\n\
+                                         0x12345678 totalSupply()\n\
+                                         transfer(address,uint256)\n").unwrap();
+
+        assert_eq!(registry.len(), 2);
+        assert_eq!(report.loaded, 2);
+        assert_eq!(
+            report.rejected.iter().map(|r| &r.0[..]).collect::<Vec<_>>(),
+            vec!["0x095ea7b3 approve(address,uint256", "0x12345678 totalSupply()"]
+        );
+        assert!(report.rejected[1].1.contains("does not hash to selector 0x12345678"));
+    }
+
+    #[test]
+    fn tries_candidates_until_one_decodes() {
+        let mut registry = SelectorRegistry::new();
+        registry.load_json(include_str!("../../../test_data/selectors.json")).unwrap();
+
+        // The first candidate for 0xa9059cbb takes a bytes1, which can't decode this input.
+        let call = registry.decode_input(TRANSFER_INPUT).unwrap();
+
+        assert_eq!(call.function.signature(), "transfer(address,uint256)");
+        assert_eq!(call.values, vec![
+            AbiValue::Address(H160::from_str("461680c41786ec889ac9de7f5e89a2f4e0fcb156").unwrap()),
+            AbiValue::Uint(U256::from(1000))
+        ]);
+        match call.get_function() {
+            ContractFunction::Mutable(name) => assert_eq!(name, "transfer(address,uint256)"),
+            _ => panic!("transfer should be mutable")
+        }
+        assert!(registry.decode_input("0xdeadbeef").is_err());
+    }
+
+    #[test]
+    fn decodes_trace_inputs_of_known_contracts() {
+        let traces = proxy_traces();
+        let registry = SelectorRegistry::from_contract(&ETHERDELTA_ABI);
+
+        let calls = traces.iter()
+            .filter_map(|t| registry.decode_trace(t).ok())
+            .map(|c| c.function.name)
+            .collect::<Vec<String>>();
+
+        assert_eq!(calls.iter().filter(|n| *n == "availableVolume").count(), 3);
+        assert_eq!(calls.iter().filter(|n| *n == "trade").count(), 2);
+    }
+
+    #[test]
+    fn resolves_transfer_layouts_by_topic_count() {
+        let registry = EventRegistry::standard();
+        let topic = H256::from_str(TRANSFER_TOPIC).unwrap();
+
+        let erc20 = log(H160::zero(), vec![topic, word(1), word(2)], &format!("0x{:?}", word(1000)));
+        let erc721 = log(H160::zero(), vec![topic, word(1), word(2), word(7)], "0x");
+        let erc20 = registry.decode_log(&erc20).unwrap();
+        let erc721 = registry.decode_log(&erc721).unwrap();
+
+        assert_eq!(registry.candidates(&topic).len(), 2);
+        assert_eq!(erc20.get("value"), Some(&AbiValue::Uint(U256::from(1000))));
+        assert_eq!(erc721.get("tokenId"), Some(&AbiValue::Uint(U256::from(7))));
+        assert!(registry.decode_log(&log(H160::zero(), vec![topic, word(1)], "0x")).is_err());
+    }
+
+    #[test]
+    fn decodes_seeded_weth_and_etherdelta_events() {
+        let registry = EventRegistry::standard();
+        let weth_deposit = "event Deposit(address indexed dst, uint256 wad)".parse::<::contracts::abi::Event>()
+            .unwrap()
+            .topic();
+
+        let deposit = log(H160::zero(), vec![weth_deposit, word(1)], &format!("0x{:?}", word(5)));
+        let deposit = registry.decode_log(&deposit).unwrap();
+        let withdraw = registry.decode_log(&log(
+            H160::zero(),
+            vec![*ETHERDELTA_WITHDRAW_TOPIC],
+            &format!("0x{:?}{:?}{:?}{:?}", word(0), word(1), word(5), word(0))
+        )).unwrap();
+
+        assert_eq!(deposit.uint("wad").unwrap(), U256::from(5));
+        assert_eq!(withdraw.event, "Withdraw");
+        assert_eq!(withdraw.uint("amount").unwrap(), U256::from(5));
+    }
+
+    #[test]
+    fn loads_event_signatures() {
+        let mut registry = EventRegistry::new();
+        registry.load_json(&format!(
+            r#"{{"0x{}": ["event Transfer(address indexed from, address indexed to, uint256 value)"]}}"#,
+            TRANSFER_TOPIC
+        )).unwrap();
+        let report = registry.load_text("# WETH\n\
+                                         event Withdrawal(address indexed src, uint256 wad)\n\
+                                         event Deposit(address indexed dst, uint256\n").unwrap();
+
+        assert_eq!(registry.len(), 2);
+        assert_eq!(report.loaded, 1);
+        assert_eq!(report.rejected[0].0, "event Deposit(address indexed dst, uint256");
+
+        let report = registry.load_json(r#"{"0x0000000000000000000000000000000000000000000000000000000000000000": ["Ping()"]}"#)
+            .unwrap();
+        assert_eq!((report.loaded, report.rejected.len()), (0, 1));
+        assert!(registry.load_json("[]").is_err());
+    }
+}
@@ -0,0 +1,290 @@
+use std::str::FromStr;
+
+use error::{Error, ErrorKind};
+
+use super::contract::{AbiError, Contract, Event, EventParam, Function, Param, StateMutability};
+use super::types::{split_top_level, ParamType};
+
+impl FromStr for Function {
+    type Err = Error;
+
+    /// Parses a human-readable function signature, e.g. `withdraw(uint256)` or
+    /// `function balanceOf(address owner) view returns (uint256)`.
+    fn from_str(s: &str) -> Result<Function, Error> {
+        let s = strip_keyword(s.trim(), "function");
+        let (name, inputs, rest) = split_signature(s)?;
+
+        let mut outputs = Vec::new();
+        let mut state_mutability = StateMutability::NonPayable;
+        let mut rest = rest.trim();
+
+        while !rest.is_empty() {
+            let (word, tail) = next_word(rest);
+
+            match word {
+                "returns" => {
+                    let tail = tail.trim_start();
+                    let close = tail.strip_prefix('(').and_then(|t| matching_paren(t).map(|i| i + 1))
+                        .ok_or_else(|| abi_error(format!("Invalid returns clause: {}", s)))?;
+                    outputs = params(&tail[1..close])?;
+                    rest = tail[close + 1..].trim();
+                    continue;
+                },
+                "pure" => state_mutability = StateMutability::Pure,
+                "view" | "constant" => state_mutability = StateMutability::View,
+                "payable" => state_mutability = StateMutability::Payable,
+                "nonpayable" | "external" | "public" | "internal" | "private" | "virtual" | "override" => {},
+                _ => return Err(abi_error(format!("Unexpected `{}` in signature: {}", word, s)))
+            }
+
+            rest = tail.trim();
+        }
+
+        Ok(Function::new(name, params(inputs)?, outputs, state_mutability))
+    }
+}
+
+impl FromStr for Event {
+    type Err = Error;
+
+    /// Parses a human-readable event signature, e.g. `Deposit(address,address,uint256,uint256)`
+    /// or `event Transfer(address indexed from, address indexed to, uint256 value)`.
+    fn from_str(s: &str) -> Result<Event, Error> {
+        let s = strip_keyword(s.trim(), "event");
+        let (name, inputs, rest) = split_signature(s)?;
+
+        let anonymous = match rest.trim() {
+            "" => false,
+            "anonymous" => true,
+            other => return Err(abi_error(format!("Unexpected `{}` in signature: {}", other, s)))
+        };
+
+        let inputs = split_top_level(inputs)?
+            .into_iter()
+            .map(|p| parse_param(p).map(|(kind, indexed, name)| EventParam { name, kind, indexed }))
+            .collect::<Result<Vec<EventParam>, Error>>()?;
+
+        Ok(Event::new(name, inputs, anonymous))
+    }
+}
+
+impl FromStr for AbiError {
+    type Err = Error;
+
+    /// Parses a human-readable error signature, e.g. `error InsufficientBalance(uint256,uint256)`.
+    fn from_str(s: &str) -> Result<AbiError, Error> {
+        let s = strip_keyword(s.trim(), "error");
+        let (name, inputs, rest) = split_signature(s)?;
+
+        if !rest.trim().is_empty() {
+            return Err(abi_error(format!("Unexpected `{}` in signature: {}", rest.trim(), s)));
+        }
+
+        Ok(AbiError { name, inputs: params(inputs)? })
+    }
+}
+
+impl Contract {
+    /// Builds a contract from human-readable signatures.  Entries starting with `event` or
+    /// `error` are parsed as such, everything else as a function.
+    pub fn from_signatures<'a, I>(signatures: I) -> Result<Contract, Error>
+        where I: IntoIterator<Item = &'a str>
+    {
+        let mut contract = Contract::default();
+
+        for signature in signatures {
+            let signature = signature.trim();
+            match next_word(signature).0 {
+                "event" => contract.events.push(signature.parse()?),
+                "error" => contract.errors.push(signature.parse()?),
+                _ => contract.functions.push(signature.parse()?)
+            }
+        }
+
+        Ok(contract)
+    }
+}
+
+/// Splits `name(params) rest` into its three parts.
+fn split_signature(s: &str) -> Result<(String, &str, &str), Error> {
+    let open = s.find('(')
+        .ok_or_else(|| abi_error(format!("Missing parameter list: {}", s)))?;
+    let close = matching_paren(&s[open + 1..])
+        .map(|i| i + open + 1)
+        .ok_or_else(|| abi_error(format!("Unbalanced parentheses: {}", s)))?;
+
+    let name = s[..open].trim();
+    if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '$') {
+        return Err(abi_error(format!("Invalid name in signature: {}", s)));
+    }
+
+    Ok((name.to_string(), &s[open + 1..close], &s[close + 1..]))
+}
+
+fn params(s: &str) -> Result<Vec<Param>, Error> {
+    split_top_level(s)?
+        .into_iter()
+        .map(|p| {
+            let (kind, indexed, name) = parse_param(p)?;
+            if indexed {
+                return Err(abi_error(format!("Only event parameters can be indexed: {}", p)));
+            }
+            Ok(Param { name, kind, internal_type: None })
+        })
+        .collect()
+}
+
+/// Parses `type [indexed] [location] [name]`, where `type` may be a tuple whose members
+/// are themselves named, e.g. `(address maker, uint256 amount)[] orders`.
+fn parse_param(s: &str) -> Result<(ParamType, bool, String), Error> {
+    let s = s.trim();
+    let tuple = strip_keyword(s, "tuple");
+
+    let (kind, rest) = if let Some(inner) = tuple.strip_prefix('(') {
+        let close = matching_paren(inner)
+            .ok_or_else(|| abi_error(format!("Unbalanced parentheses: {}", s)))?;
+        let members = split_top_level(&inner[..close])?
+            .into_iter()
+            .map(|m| parse_param(m).map(|(kind, _, _)| kind))
+            .collect::<Result<Vec<ParamType>, Error>>()?;
+        let after = &inner[close + 1..];
+        let (suffix, rest) = after.split_at(
+            after.find(|c: char| !(c == '[' || c == ']' || c.is_ascii_digit())).unwrap_or(after.len())
+        );
+        let kind = format!("{}{}", ParamType::Tuple(members), suffix).parse::<ParamType>()?;
+        (kind, rest)
+    } else {
+        let (word, rest) = next_word(s);
+        (word.parse::<ParamType>()?, rest)
+    };
+
+    let mut indexed = false;
+    let mut name = String::new();
+
+    for word in rest.split_whitespace() {
+        match word {
+            "indexed" => indexed = true,
+            "memory" | "calldata" | "storage" | "payable" => {},
+            _ if name.is_empty() => name = word.to_string(),
+            _ => return Err(abi_error(format!("Invalid parameter: {}", s)))
+        }
+    }
+
+    Ok((kind, indexed, name))
+}
+
+/// Index of the `)` closing a parenthesis opened just before `s`.
+fn matching_paren(s: &str) -> Option<usize> {
+    let mut depth = 0usize;
+
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => return Some(i),
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Removes a leading keyword if it is followed by whitespace or `(`.
+fn strip_keyword<'a>(s: &'a str, keyword: &str) -> &'a str {
+    match s.strip_prefix(keyword) {
+        Some(rest) if rest.starts_with(|c: char| c.is_whitespace() || c == '(') => rest.trim_start(),
+        _ => s
+    }
+}
+
+fn next_word(s: &str) -> (&str, &str) {
+    let s = s.trim_start();
+    let end = s.find(|c: char| c.is_whitespace() || c == '(').unwrap_or(s.len());
+    (&s[..end], &s[end..])
+}
+
+fn abi_error(message: String) -> Error {
+    ErrorKind::Abi(message).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use contracts::abi::{AbiError, Contract, Event, Function, ParamType, StateMutability};
+    use contracts::etherdelta::constants::ETHERDELTA_ABI;
+
+    #[test]
+    fn parses_bare_function_signatures() {
+        let trade = "trade(address,uint256,address,uint256,uint256,uint256,address,uint8,bytes32,bytes32,uint256)"
+            .parse::<Function>()
+            .unwrap();
+        let from_json = ETHERDELTA_ABI.function("trade").unwrap();
+
+        assert_eq!(trade.method_id(), "0x0a19b14a");
+        assert_eq!(
+            trade.inputs.iter().map(|p| &p.kind).collect::<Vec<_>>(),
+            from_json.inputs.iter().map(|p| &p.kind).collect::<Vec<_>>()
+        );
+        assert_eq!(trade.state_mutability, StateMutability::NonPayable);
+    }
+
+    #[test]
+    fn parses_full_function_declarations() {
+        let f = "function balanceOf(address token, address user) constant returns (uint256)"
+            .parse::<Function>()
+            .unwrap();
+
+        assert_eq!(&f, ETHERDELTA_ABI.function("balanceOf").unwrap());
+    }
+
+    #[test]
+    fn parses_events_like_the_json_abi() {
+        let json = Contract::load(r#"[{"type":"event","name":"Transfer","anonymous":false,"inputs":[
+            {"name":"from","type":"address","indexed":true},
+            {"name":"to","type":"address","indexed":true},
+            {"name":"value","type":"uint256","indexed":false}]}]"#).unwrap();
+        let event = "event Transfer(address indexed from, address indexed to, uint256 value)"
+            .parse::<Event>()
+            .unwrap();
+
+        assert_eq!(&event, json.event("Transfer").unwrap());
+        assert!("event Ping(uint256) anonymous".parse::<Event>().unwrap().anonymous);
+    }
+
+    #[test]
+    fn parses_tuples_and_arrays() {
+        let f = "function fill((address maker, uint256[2] amounts)[] calldata orders, bytes[] sigs) payable"
+            .parse::<Function>()
+            .unwrap();
+
+        assert_eq!(f.signature(), "fill((address,uint256[2])[],bytes[])");
+        assert_eq!(f.inputs[0].name, "orders");
+        assert_eq!(f.state_mutability, StateMutability::Payable);
+        assert_eq!(
+            "f(tuple(uint8,string) t)".parse::<Function>().unwrap().inputs[0].kind,
+            ParamType::Tuple(vec![ParamType::Uint(8), ParamType::String])
+        );
+    }
+
+    #[test]
+    fn builds_contracts_from_signatures() {
+        let contract = Contract::from_signatures(vec![
+            "function transfer(address to, uint256 value) returns (bool)",
+            "event Approval(address indexed owner, address indexed spender, uint256 value)",
+            "error InsufficientBalance(uint256 available, uint256 required)"
+        ]).unwrap();
+
+        assert_eq!(contract.function("transfer").unwrap().method_id(), "0xa9059cbb");
+        assert_eq!(contract.events[0].inputs.iter().filter(|p| p.indexed).count(), 2);
+        assert_eq!(contract.error("InsufficientBalance").unwrap().selector(), [0xcf, 0x47, 0x91, 0x81]);
+    }
+
+    #[test]
+    fn rejects_malformed_signatures() {
+        assert!("transfer(address,uint256".parse::<Function>().is_err());
+        assert!("transfer address".parse::<Function>().is_err());
+        assert!("f(uint256 indexed a)".parse::<Function>().is_err());
+        assert!("f(uint256) banana".parse::<Function>().is_err());
+        assert!("Ping(uint7)".parse::<Event>().is_err());
+        assert!("error E(uint256) view".parse::<AbiError>().is_err());
+    }
+}
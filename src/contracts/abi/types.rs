@@ -0,0 +1,346 @@
+use std::fmt;
+use std::str::FromStr;
+
+use error::{Error, ErrorKind};
+use types::{H160, U256};
+
+use super::decoder::sign_extend;
+
+/// A Solidity ABI type.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ParamType {
+    Address,
+    Bool,
+    /// `int<M>`, with `M` the number of bits.
+    Int(usize),
+    /// `uint<M>`, with `M` the number of bits.
+    Uint(usize),
+    /// `bytes<M>`, with `M` the number of bytes.
+    FixedBytes(usize),
+    Bytes,
+    String,
+    /// `T[]`
+    Array(Box<ParamType>),
+    /// `T[k]`
+    FixedArray(Box<ParamType>, usize),
+    /// `(T1,T2,...,Tn)`
+    Tuple(Vec<ParamType>)
+}
+
+impl ParamType {
+    /// Is this type encoded in the tail of its enclosing tuple?
+    pub fn is_dynamic(&self) -> bool {
+        match *self {
+            ParamType::Bytes | ParamType::String | ParamType::Array(_) => true,
+            ParamType::FixedArray(ref t, n) => n > 0 && t.is_dynamic(),
+            ParamType::Tuple(ref ts) => ts.iter().any(|t| t.is_dynamic()),
+            _ => false
+        }
+    }
+
+    /// Checks the sizes of the type and its members: `int<M>` and `uint<M>` need `0 < M <= 256`
+    /// with `M % 8 == 0`, and `bytes<M>` needs `0 < M <= 32`.  Parsed types always pass;
+    /// decoding rejects types built directly that don't.
+    pub fn validate(&self) -> Result<(), Error> {
+        match *self {
+            ParamType::Int(bits) | ParamType::Uint(bits) if bits == 0 || bits > 256 || bits % 8 != 0 => {
+                Err(abi_error(format!("Invalid integer width: {:?}", self)))
+            },
+            ParamType::FixedBytes(len) if len == 0 || len > 32 => {
+                Err(abi_error(format!("Invalid fixed bytes length: {:?}", self)))
+            },
+            ParamType::Array(ref t) | ParamType::FixedArray(ref t, _) => t.validate(),
+            ParamType::Tuple(ref ts) => ts.iter().try_for_each(|t| t.validate()),
+            _ => Ok(())
+        }
+    }
+
+    /// Number of bytes the type occupies in the head of its enclosing tuple.
+    pub fn head_size(&self) -> usize {
+        if self.is_dynamic() {
+            return 32;
+        }
+
+        match *self {
+            ParamType::FixedArray(ref t, n) => t.head_size() * n,
+            ParamType::Tuple(ref ts) => ts.iter().map(|t| t.head_size()).sum(),
+            _ => 32
+        }
+    }
+}
+
+impl FromStr for ParamType {
+    type Err = Error;
+
+    /// Parses a type name such as `uint`, `bytes32[]` or `(address,uint256)[2]`.  Aliases
+    /// are normalized (`uint` is `uint256`, `function` is `bytes24`).
+    fn from_str(s: &str) -> Result<ParamType, Error> {
+        let s = s.trim();
+
+        if s.ends_with(']') {
+            let open = s.rfind('[')
+                .ok_or_else(|| abi_error(format!("Invalid array type: {}", s)))?;
+            let inner = Box::new(s[..open].parse::<ParamType>()?);
+            let size = &s[open + 1..s.len() - 1];
+
+            return if size.is_empty() {
+                Ok(ParamType::Array(inner))
+            } else {
+                size.parse::<usize>()
+                    .map(|n| ParamType::FixedArray(inner, n))
+                    .map_err(|_| abi_error(format!("Invalid array size: {}", s)))
+            };
+        }
+
+        if s.starts_with('(') && s.ends_with(')') {
+            return split_top_level(&s[1..s.len() - 1])?
+                .into_iter()
+                .map(|t| t.parse::<ParamType>())
+                .collect::<Result<Vec<ParamType>, Error>>()
+                .map(ParamType::Tuple);
+        }
+
+        match s {
+            "address" => Ok(ParamType::Address),
+            "bool" => Ok(ParamType::Bool),
+            "string" => Ok(ParamType::String),
+            "bytes" => Ok(ParamType::Bytes),
+            "function" => Ok(ParamType::FixedBytes(24)),
+            "int" => Ok(ParamType::Int(256)),
+            "uint" => Ok(ParamType::Uint(256)),
+            _ => {
+                let sized = |prefix: &str, max: usize, step: usize| -> Option<usize> {
+                    s.strip_prefix(prefix)
+                        .and_then(|n| n.parse::<usize>().ok())
+                        .filter(|n| *n > 0 && *n <= max && n % step == 0)
+                };
+
+                if let Some(n) = sized("uint", 256, 8) {
+                    Ok(ParamType::Uint(n))
+                } else if let Some(n) = sized("int", 256, 8) {
+                    Ok(ParamType::Int(n))
+                } else if let Some(n) = sized("bytes", 32, 1) {
+                    Ok(ParamType::FixedBytes(n))
+                } else {
+                    Err(abi_error(format!("Unsupported type: {}", s)))
+                }
+            }
+        }
+    }
+}
+
+/// Splits a comma separated list of types, ignoring commas nested in parentheses.
+pub(crate) fn split_top_level(s: &str) -> Result<Vec<&str>, Error> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth = depth.checked_sub(1)
+                    .ok_or_else(|| abi_error(format!("Unbalanced parentheses: {}", s)))?;
+            },
+            ',' if depth == 0 => {
+                parts.push(s[start..i].trim());
+                start = i + 1;
+            },
+            _ => {}
+        }
+    }
+
+    if depth != 0 {
+        return Err(abi_error(format!("Unbalanced parentheses: {}", s)));
+    }
+
+    let last = s[start..].trim();
+    if !last.is_empty() || !parts.is_empty() {
+        parts.push(last);
+    }
+
+    Ok(parts)
+}
+
+fn abi_error(message: String) -> Error {
+    ErrorKind::Abi(message).into()
+}
+
+impl fmt::Display for ParamType {
+    /// Canonical type name, as used in function and event signatures.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParamType::Address => write!(f, "address"),
+            ParamType::Bool => write!(f, "bool"),
+            ParamType::Int(n) => write!(f, "int{}", n),
+            ParamType::Uint(n) => write!(f, "uint{}", n),
+            ParamType::FixedBytes(n) => write!(f, "bytes{}", n),
+            ParamType::Bytes => write!(f, "bytes"),
+            ParamType::String => write!(f, "string"),
+            ParamType::Array(ref t) => write!(f, "{}[]", t),
+            ParamType::FixedArray(ref t, n) => write!(f, "{}[{}]", t, n),
+            ParamType::Tuple(ref ts) => {
+                let names = ts.iter().map(|t| t.to_string()).collect::<Vec<String>>();
+                write!(f, "({})", names.join(","))
+            }
+        }
+    }
+}
+
+/// A typed ABI value.  Signed integers are stored in their two's complement form.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AbiValue {
+    Address(H160),
+    Bool(bool),
+    Int(U256),
+    Uint(U256),
+    FixedBytes(Vec<u8>),
+    Bytes(Vec<u8>),
+    String(String),
+    Array(Vec<AbiValue>),
+    FixedArray(Vec<AbiValue>),
+    Tuple(Vec<AbiValue>)
+}
+
+impl AbiValue {
+    /// Is this value encoded in the tail of its enclosing tuple?
+    pub fn is_dynamic(&self) -> bool {
+        match *self {
+            AbiValue::Bytes(_) | AbiValue::String(_) | AbiValue::Array(_) => true,
+            AbiValue::FixedArray(ref vs) | AbiValue::Tuple(ref vs) => {
+                vs.iter().any(|v| v.is_dynamic())
+            },
+            _ => false
+        }
+    }
+
+    /// Can this value be encoded as the given type?
+    pub fn type_check(&self, param_type: &ParamType) -> bool {
+        param_type.validate().is_ok() && self.matches_type(param_type)
+    }
+
+    fn matches_type(&self, param_type: &ParamType) -> bool {
+        match (self, param_type) {
+            (AbiValue::Address(_), ParamType::Address) => true,
+            (AbiValue::Bool(_), ParamType::Bool) => true,
+            (AbiValue::Int(v), ParamType::Int(n)) => sign_extend(*v, *n) == *v,
+            (AbiValue::Uint(v), ParamType::Uint(n)) => v.bits() <= *n,
+            (AbiValue::FixedBytes(b), ParamType::FixedBytes(n)) => b.len() == *n,
+            (AbiValue::Bytes(_), ParamType::Bytes) => true,
+            (AbiValue::String(_), ParamType::String) => true,
+            (AbiValue::Array(vs), ParamType::Array(t)) => {
+                vs.iter().all(|v| v.matches_type(t))
+            },
+            (AbiValue::FixedArray(vs), ParamType::FixedArray(t, n)) => {
+                vs.len() == *n && vs.iter().all(|v| v.matches_type(t))
+            },
+            (AbiValue::Tuple(vs), ParamType::Tuple(ts)) => {
+                vs.len() == ts.len() && vs.iter().zip(ts.iter()).all(|(v, t)| v.matches_type(t))
+            },
+            _ => false
+        }
+    }
+
+    pub fn to_address(&self) -> Option<H160> {
+        match *self {
+            AbiValue::Address(a) => Some(a),
+            _ => None
+        }
+    }
+
+    pub fn to_bool(&self) -> Option<bool> {
+        match *self {
+            AbiValue::Bool(b) => Some(b),
+            _ => None
+        }
+    }
+
+    /// The raw 256 bit word of an integer value, signed or not.
+    pub fn to_uint(&self) -> Option<U256> {
+        match *self {
+            AbiValue::Int(n) | AbiValue::Uint(n) => Some(n),
+            _ => None
+        }
+    }
+
+    pub fn to_bytes(&self) -> Option<&[u8]> {
+        match *self {
+            AbiValue::FixedBytes(ref b) | AbiValue::Bytes(ref b) => Some(b),
+            _ => None
+        }
+    }
+
+    pub fn to_str(&self) -> Option<&str> {
+        match *self {
+            AbiValue::String(ref s) => Some(s),
+            _ => None
+        }
+    }
+
+    /// Members of an array, fixed-size array or tuple.
+    pub fn to_values(&self) -> Option<&[AbiValue]> {
+        match *self {
+            AbiValue::Array(ref vs) | AbiValue::FixedArray(ref vs) | AbiValue::Tuple(ref vs) => {
+                Some(vs)
+            },
+            _ => None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use types::U256;
+    use super::{AbiValue, ParamType};
+
+    #[test]
+    fn displays_canonical_type_names() {
+        let t = ParamType::Array(Box::new(ParamType::Tuple(vec![
+            ParamType::Address,
+            ParamType::FixedArray(Box::new(ParamType::Uint(256)), 2),
+            ParamType::Bytes
+        ])));
+
+        assert_eq!(t.to_string(), "(address,uint256[2],bytes)[]");
+    }
+
+    #[test]
+    fn computes_dynamic_types_and_head_sizes() {
+        let static_tuple = ParamType::Tuple(vec![ParamType::Address, ParamType::Uint(8)]);
+        let dynamic_tuple = ParamType::Tuple(vec![ParamType::Address, ParamType::String]);
+
+        assert!(!static_tuple.is_dynamic());
+        assert_eq!(static_tuple.head_size(), 64);
+        assert!(dynamic_tuple.is_dynamic());
+        assert_eq!(dynamic_tuple.head_size(), 32);
+        assert_eq!(ParamType::FixedArray(Box::new(static_tuple), 3).head_size(), 192);
+    }
+
+    #[test]
+    fn parses_type_names() {
+        assert_eq!("uint".parse::<ParamType>().unwrap(), ParamType::Uint(256));
+        assert_eq!("bytes32".parse::<ParamType>().unwrap(), ParamType::FixedBytes(32));
+        assert_eq!(
+            "(address,(uint8,string)[])[2]".parse::<ParamType>().unwrap(),
+            ParamType::FixedArray(Box::new(ParamType::Tuple(vec![
+                ParamType::Address,
+                ParamType::Array(Box::new(ParamType::Tuple(vec![ParamType::Uint(8), ParamType::String])))
+            ])), 2)
+        );
+        assert_eq!("()".parse::<ParamType>().unwrap(), ParamType::Tuple(vec![]));
+
+        assert!("uint7".parse::<ParamType>().is_err());
+        assert!("bytes33".parse::<ParamType>().is_err());
+        assert!("(address".parse::<ParamType>().is_err());
+        assert!("fixed128x18".parse::<ParamType>().is_err());
+    }
+
+    #[test]
+    fn type_checks_values() {
+        assert!(AbiValue::Uint(U256::from(255)).type_check(&ParamType::Uint(8)));
+        assert!(!AbiValue::Uint(U256::from(256)).type_check(&ParamType::Uint(8)));
+        assert!(!AbiValue::FixedBytes(vec![0; 3]).type_check(&ParamType::FixedBytes(4)));
+        assert!(!AbiValue::Int(U256::zero()).type_check(&ParamType::Int(0)));
+        assert!(!AbiValue::Array(vec![]).type_check(&ParamType::Array(Box::new(ParamType::Uint(7)))));
+    }
+}
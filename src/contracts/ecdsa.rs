@@ -0,0 +1,165 @@
+use std::fmt;
+use std::str::FromStr;
+
+use fixed_hash::clean_0x;
+use libsecp256k1::{self, Message, RecoveryId};
+use rustc_serialize::hex::{FromHex, ToHex};
+
+use contracts::abi::keccak256;
+use error::{Error, ErrorKind};
+use types::{H160, H256, U256};
+
+/// A recoverable secp256k1 signature, as produced by `eth_sign` and `ecrecover`'s inputs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Signature {
+    /// Recovery id, either `0`/`1` or `27`/`28`.
+    pub v: u8,
+    pub r: H256,
+    pub s: H256
+}
+
+impl Signature {
+    pub fn new(v: u8, r: H256, s: H256) -> Signature {
+        Signature { v, r, s }
+    }
+
+    /// Builds a signature from `v`, `r` and `s` in the form stored by `OrderData`: `r` and `s`
+    /// as 32 byte hex strings, with or without `0x`.
+    pub fn from_vrs(v: &U256, r: &str, s: &str) -> Result<Signature, Error> {
+        if v.bits() > 8 {
+            return Err(signature_error(format!("Invalid v: {}", v)));
+        }
+
+        Ok(Signature { v: v.low_u64() as u8, r: parse_word(r)?, s: parse_word(s)? })
+    }
+
+    /// Recovers the address whose key signed `hash`.
+    pub fn recover(&self, hash: &H256) -> Result<H160, Error> {
+        let recovery_id = match self.v {
+            0 | 1 => self.v,
+            27 | 28 => self.v - 27,
+            v => return Err(signature_error(format!("Invalid v: {}", v)))
+        };
+
+        let mut rs = [0u8; 64];
+        rs[..32].copy_from_slice(&self.r);
+        rs[32..].copy_from_slice(&self.s);
+
+        let signature = libsecp256k1::Signature::parse_standard(&rs)
+            .map_err(|e| signature_error(format!("Invalid r or s: {:?}", e)))?;
+        let recovery_id = RecoveryId::parse(recovery_id)
+            .map_err(|e| signature_error(format!("Invalid v: {:?}", e)))?;
+        let message = Message::parse(&hash.0);
+
+        let public_key = libsecp256k1::recover(&message, &signature, &recovery_id)
+            .map_err(|e| signature_error(format!("Couldn't recover public key: {:?}", e)))?;
+
+        // Uncompressed keys are serialized as `0x04 || x || y`.
+        let hash = keccak256(&public_key.serialize()[1..]);
+        Ok(H160::from_slice(&hash.0[12..]))
+    }
+
+    /// Does the signature of `hash` recover to `signer`?
+    pub fn verify(&self, hash: &H256, signer: &H160) -> bool {
+        self.recover(hash).map(|a| a == *signer).unwrap_or(false)
+    }
+}
+
+impl FromStr for Signature {
+    type Err = Error;
+
+    /// Parses the 65 byte `r || s || v` hex encoding returned by `eth_sign`.
+    fn from_str(s: &str) -> Result<Signature, Error> {
+        let bytes = clean_0x(s).from_hex()
+            .map_err(|e| signature_error(format!("Invalid hex ({}): {}", e, s)))?;
+
+        if bytes.len() != 65 {
+            return Err(signature_error(format!("Expected 65 bytes, got {}", bytes.len())));
+        }
+
+        Ok(Signature { v: bytes[64], r: H256::from_slice(&bytes[..32]), s: H256::from_slice(&bytes[32..64]) })
+    }
+}
+
+impl fmt::Display for Signature {
+    /// The 65 byte `r || s || v` hex encoding.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "0x{}{}{:02x}", self.r.to_hex(), self.s.to_hex(), self.v)
+    }
+}
+
+/// The hash signed by `eth_sign`/`personal_sign` for a message: keccak256 of
+/// `"\x19Ethereum Signed Message:\n" || len(message) || message`.
+pub fn eth_message_hash(message: &[u8]) -> H256 {
+    let mut data = format!("\x19Ethereum Signed Message:\n{}", message.len()).into_bytes();
+    data.extend_from_slice(message);
+    keccak256(&data)
+}
+
+fn parse_word(s: &str) -> Result<H256, Error> {
+    let bytes = clean_0x(s).from_hex()
+        .map_err(|e| signature_error(format!("Invalid hex ({}): {}", e, s)))?;
+
+    if bytes.len() != 32 {
+        return Err(signature_error(format!("Expected 32 bytes, got {}: {}", bytes.len(), s)));
+    }
+
+    Ok(H256::from_slice(&bytes))
+}
+
+fn signature_error(message: String) -> Error {
+    ErrorKind::Signature(message).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use types::{H160, H256, U256};
+    use super::{eth_message_hash, Signature};
+
+    // The "Ether Mail" example of EIP-712, signed by the key `keccak256("cow")`.
+    const MAIL_HASH: &str = "be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2";
+    const MAIL_R: &str = "4355c47d63924e8a72e509b65029052eb6c299d53a04e167c5775fd466751c9d";
+    const MAIL_S: &str = "07299936d304c153f6443dfa05f40ff007d72911b6f72307f996231605b91562";
+    const COW: &str = "cd2a3d9f938e13cd947ec05abc7fe734df8dd826";
+
+    #[test]
+    fn recovers_signers() {
+        let hash = H256::from_str(MAIL_HASH).unwrap();
+        let signature = Signature::from_vrs(&U256::from(28), MAIL_R, &format!("0x{}", MAIL_S)).unwrap();
+
+        assert_eq!(signature.recover(&hash).unwrap(), H160::from_str(COW).unwrap());
+        assert!(signature.verify(&hash, &H160::from_str(COW).unwrap()));
+        assert!(!Signature { v: 27, ..signature }.verify(&hash, &H160::from_str(COW).unwrap()));
+    }
+
+    #[test]
+    fn parses_rsv_hex() {
+        let signature = format!("0x{}{}1c", MAIL_R, MAIL_S).parse::<Signature>().unwrap();
+
+        assert_eq!(signature.v, 28);
+        assert_eq!(signature.to_string(), format!("0x{}{}1c", MAIL_R, MAIL_S));
+        assert!("0x1234".parse::<Signature>().is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_signatures() {
+        let hash = H256::from_str(MAIL_HASH).unwrap();
+
+        assert!(Signature::from_vrs(&U256::from(256), MAIL_R, MAIL_S).is_err());
+        assert!(Signature::from_vrs(&U256::from(28), "0x12", MAIL_S).is_err());
+        assert!(Signature::new(29, H256::from_str(MAIL_R).unwrap(), H256::from_str(MAIL_S).unwrap())
+            .recover(&hash)
+            .is_err());
+        assert!(Signature::new(27, H256::zero(), H256::zero()).recover(&hash).is_err());
+    }
+
+    #[test]
+    fn hashes_eth_signed_messages() {
+        assert_eq!(
+            eth_message_hash(b"hello"),
+            H256::from_str("50b2c43fd39106bafbba0da34fc430e1f91e3c96ea2acee2bc34119f92b37750").unwrap()
+        );
+    }
+}
@@ -0,0 +1,413 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use fixed_hash::clean_0x;
+use rustc_serialize::hex::FromHex;
+use serde_json::{self, Value};
+
+use contracts::abi::{encode, keccak256, AbiValue, ParamType};
+use contracts::ecdsa::Signature;
+use error::{Error, ErrorKind};
+use types::{H160, H256, U256};
+
+const DOMAIN_TYPE: &str = "EIP712Domain";
+
+/// Fields an `EIP712Domain` may have, in the order they are declared when the document
+/// doesn't define the domain's type itself.
+const DOMAIN_FIELDS: &[(&str, &str)] = &[
+    ("name", "string"),
+    ("version", "string"),
+    ("chainId", "uint256"),
+    ("verifyingContract", "address"),
+    ("salt", "bytes32")
+];
+
+/// A member of an EIP-712 struct type.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TypedField {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: String
+}
+
+/// An EIP-712 typed data document, as passed to `eth_signTypedData_v4`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypedData {
+    pub types: BTreeMap<String, Vec<TypedField>>,
+    pub primary_type: String,
+    pub domain: Value,
+    pub message: Value
+}
+
+impl TypedData {
+    pub fn load(json: &str) -> Result<TypedData, Error> {
+        let mut data = serde_json::from_str::<TypedData>(json)?;
+
+        if !data.types.contains_key(DOMAIN_TYPE) {
+            let fields = DOMAIN_FIELDS.iter()
+                .filter(|(name, _)| data.domain.get(name).is_some())
+                .map(|(name, kind)| TypedField { name: name.to_string(), kind: kind.to_string() })
+                .collect();
+            data.types.insert(DOMAIN_TYPE.to_string(), fields);
+        }
+
+        Ok(data)
+    }
+
+    pub fn load_file<P: AsRef<Path>>(path: P) -> Result<TypedData, Error> {
+        let mut json = String::new();
+        File::open(path)?.read_to_string(&mut json)?;
+        TypedData::load(&json)
+    }
+
+    /// `encodeType`: the type's own declaration followed by those of the struct types it
+    /// references, sorted by name, e.g. `Mail(Person from,Person to,string contents)Person(...)`.
+    pub fn encode_type(&self, name: &str) -> Result<String, Error> {
+        let mut dependencies = BTreeSet::new();
+        self.collect_dependencies(name, &mut dependencies)?;
+        dependencies.remove(name);
+
+        let mut encoded = self.declaration(name)?;
+        for dependency in dependencies {
+            encoded.push_str(&self.declaration(dependency)?);
+        }
+
+        Ok(encoded)
+    }
+
+    pub fn type_hash(&self, name: &str) -> Result<H256, Error> {
+        self.encode_type(name).map(|t| keccak256(t.as_bytes()))
+    }
+
+    /// `hashStruct`: keccak256 of the type hash followed by the encoded members of `value`.
+    pub fn hash_struct(&self, name: &str, value: &Value) -> Result<H256, Error> {
+        let fields = self.fields(name)?;
+        let mut encoded = self.type_hash(name)?.to_vec();
+
+        for field in fields {
+            let member = value.get(&field.name)
+                .ok_or_else(|| typed_data_error(format!("{} is missing {}", name, field.name)))?;
+            encoded.extend_from_slice(&self.encode_field(&field.kind, member)?);
+        }
+
+        Ok(keccak256(&encoded))
+    }
+
+    pub fn domain_separator(&self) -> Result<H256, Error> {
+        self.hash_struct(DOMAIN_TYPE, &self.domain)
+    }
+
+    /// The digest that is actually signed: keccak256 of
+    /// `0x19 0x01 || domainSeparator || hashStruct(message)`.
+    pub fn signing_hash(&self) -> Result<H256, Error> {
+        let mut encoded = vec![0x19, 0x01];
+        encoded.extend_from_slice(&self.domain_separator()?);
+        encoded.extend_from_slice(&self.hash_struct(&self.primary_type, &self.message)?);
+        Ok(keccak256(&encoded))
+    }
+
+    /// The address that signed this document.
+    pub fn recover_signer(&self, signature: &Signature) -> Result<H160, Error> {
+        signature.recover(&self.signing_hash()?)
+    }
+
+    fn fields(&self, name: &str) -> Result<&Vec<TypedField>, Error> {
+        self.types.get(name).ok_or_else(|| typed_data_error(format!("Unknown type: {}", name)))
+    }
+
+    fn declaration(&self, name: &str) -> Result<String, Error> {
+        let fields = self.fields(name)?.iter()
+            .map(|f| format!("{} {}", f.kind, f.name))
+            .collect::<Vec<String>>();
+        Ok(format!("{}({})", name, fields.join(",")))
+    }
+
+    fn collect_dependencies<'a>(&'a self, name: &'a str, found: &mut BTreeSet<&'a str>) -> Result<(), Error> {
+        if !found.insert(name) {
+            return Ok(());
+        }
+
+        for field in self.fields(name)? {
+            let base = base_type(&field.kind);
+            if self.types.contains_key(base) {
+                self.collect_dependencies(base, found)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The 32 byte encoding of a member: struct and dynamic values are hashed, atomic values
+    /// are ABI-encoded.
+    fn encode_field(&self, kind: &str, value: &Value) -> Result<[u8; 32], Error> {
+        if kind.ends_with(']') {
+            let open = kind.rfind('[').ok_or_else(|| typed_data_error(format!("Invalid type: {}", kind)))?;
+            let items = value.as_array()
+                .ok_or_else(|| typed_data_error(format!("Expected an array for {}: {}", kind, value)))?;
+
+            let size = &kind[open + 1..kind.len() - 1];
+            if !size.is_empty() && size.parse::<usize>().ok() != Some(items.len()) {
+                return Err(typed_data_error(format!("Expected {} items for {}: {}", size, kind, value)));
+            }
+
+            let mut encoded = Vec::with_capacity(32 * items.len());
+            for item in items {
+                encoded.extend_from_slice(&self.encode_field(&kind[..open], item)?);
+            }
+            return Ok(keccak256(&encoded).0);
+        }
+
+        if self.types.contains_key(kind) {
+            return Ok(self.hash_struct(kind, value)?.0);
+        }
+
+        match kind {
+            "string" => value.as_str()
+                .map(|s| keccak256(s.as_bytes()).0)
+                .ok_or_else(|| typed_data_error(format!("Expected a string: {}", value))),
+            "bytes" => Ok(keccak256(&hex_value(value)?).0),
+            _ => {
+                let param_type = kind.parse::<ParamType>()?;
+                let abi_value = atomic_value(&param_type, value)?;
+
+                if !abi_value.type_check(&param_type) {
+                    return Err(typed_data_error(format!("{} is not a valid {}", value, kind)));
+                }
+
+                let mut word = [0u8; 32];
+                word.copy_from_slice(&encode(&[abi_value]));
+                Ok(word)
+            }
+        }
+    }
+}
+
+/// `Person[][2]` -> `Person`
+fn base_type(kind: &str) -> &str {
+    kind.find('[').map(|i| &kind[..i]).unwrap_or(kind)
+}
+
+fn atomic_value(kind: &ParamType, value: &Value) -> Result<AbiValue, Error> {
+    match *kind {
+        ParamType::Address => {
+            let bytes = hex_value(value)?;
+            if bytes.len() != 20 {
+                return Err(typed_data_error(format!("Invalid address: {}", value)));
+            }
+            Ok(AbiValue::Address(H160::from_slice(&bytes)))
+        },
+        ParamType::Bool => value.as_bool()
+            .map(AbiValue::Bool)
+            .ok_or_else(|| typed_data_error(format!("Expected a bool: {}", value))),
+        ParamType::Uint(_) => integer_value(value).and_then(|(negative, n)| {
+            if negative {
+                Err(typed_data_error(format!("Negative unsigned integer: {}", value)))
+            } else {
+                Ok(AbiValue::Uint(n))
+            }
+        }),
+        ParamType::Int(_) => integer_value(value).map(|(negative, n)| {
+            AbiValue::Int(if negative { n.overflowing_neg().0 } else { n })
+        }),
+        ParamType::FixedBytes(len) => {
+            let mut bytes = hex_value(value)?;
+            if bytes.len() > len {
+                return Err(typed_data_error(format!("Too many bytes for bytes{}: {}", len, value)));
+            }
+            bytes.resize(len, 0);
+            Ok(AbiValue::FixedBytes(bytes))
+        },
+        _ => Err(typed_data_error(format!("Unsupported member type: {}", kind)))
+    }
+}
+
+/// Integers may be JSON numbers, or decimal or `0x`-prefixed hex strings.  Returns the sign
+/// and the magnitude.
+fn integer_value(value: &Value) -> Result<(bool, U256), Error> {
+    let invalid = || typed_data_error(format!("Invalid integer: {}", value));
+
+    if let Some(n) = value.as_u64() {
+        return Ok((false, U256::from(n)));
+    }
+    if let Some(n) = value.as_i64() {
+        // Not a `u64`, so negative.
+        return Ok((true, U256::from(n.wrapping_neg() as u64)));
+    }
+
+    let s = value.as_str().ok_or_else(invalid)?.trim();
+    let (negative, digits) = match s.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, s)
+    };
+
+    let magnitude = match digits.strip_prefix("0x") {
+        Some(hex) => {
+            let hex = if hex.len() % 2 == 1 { format!("0{}", hex) } else { hex.to_string() };
+            let bytes = hex.from_hex().map_err(|_| invalid())?;
+            if bytes.len() > 32 {
+                return Err(invalid());
+            }
+            U256::from(&bytes[..])
+        },
+        None => U256::from_dec_str(digits).map_err(|_| invalid())?
+    };
+
+    Ok((negative, magnitude))
+}
+
+fn hex_value(value: &Value) -> Result<Vec<u8>, Error> {
+    value.as_str()
+        .and_then(|s| clean_0x(s).from_hex().ok())
+        .ok_or_else(|| typed_data_error(format!("Expected hex data: {}", value)))
+}
+
+fn typed_data_error(message: String) -> Error {
+    ErrorKind::Abi(format!("EIP-712: {}", message)).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use libsecp256k1::curve::Scalar;
+    use libsecp256k1::{Message, SecretKey, ECMULT_GEN_CONTEXT};
+
+    use contracts::abi::keccak256;
+    use contracts::ecdsa::Signature;
+    use types::{H160, H256, U256};
+    use super::TypedData;
+
+    const COW: &str = "cd2a3d9f938e13cd947ec05abc7fe734df8dd826";
+
+    fn hash(s: &str) -> H256 {
+        H256::from_str(s).unwrap()
+    }
+
+    fn mail() -> TypedData {
+        TypedData::load(include_str!("../../test_data/eip712_mail.json")).unwrap()
+    }
+
+    #[test]
+    fn encodes_and_hashes_types() {
+        let mail = mail();
+
+        assert_eq!(
+            mail.encode_type("Mail").unwrap(),
+            "Mail(Person from,Person to,string contents)Person(string name,address wallet)"
+        );
+        assert_eq!(
+            mail.type_hash("Mail").unwrap(),
+            hash("a0cedeb2dc280ba39b857546d74f5549c3a1d7bdc2dd96bf881f76108e23dac2")
+        );
+    }
+
+    #[test]
+    fn hashes_the_example_from_the_eip() {
+        let mail = mail();
+
+        assert_eq!(
+            mail.domain_separator().unwrap(),
+            hash("f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f")
+        );
+        assert_eq!(
+            mail.hash_struct("Mail", &mail.message).unwrap(),
+            hash("c52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e")
+        );
+        assert_eq!(
+            mail.signing_hash().unwrap(),
+            hash("be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2")
+        );
+    }
+
+    #[test]
+    fn recovers_the_signer() {
+        let signature = Signature::new(
+            28,
+            hash("4355c47d63924e8a72e509b65029052eb6c299d53a04e167c5775fd466751c9d"),
+            hash("07299936d304c153f6443dfa05f40ff007d72911b6f72307f996231605b91562")
+        );
+
+        assert_eq!(
+            mail().recover_signer(&signature).unwrap(),
+            H160::from_str(COW).unwrap()
+        );
+    }
+
+    #[test]
+    fn infers_the_domain_type_and_encodes_permits() {
+        let permit = TypedData::load(r#"{
+            "types": {
+                "Permit": [
+                    {"name": "owner", "type": "address"},
+                    {"name": "spender", "type": "address"},
+                    {"name": "value", "type": "uint256"},
+                    {"name": "nonce", "type": "uint256"},
+                    {"name": "deadline", "type": "uint256"}
+                ]
+            },
+            "primaryType": "Permit",
+            "domain": {"name": "Dai Stablecoin", "version": "1", "chainId": "0x1",
+                       "verifyingContract": "0x6b175474e89094c44da98b954eedeac495271d0f"},
+            "message": {"owner": "0xcd2a3d9f938e13cd947ec05abc7fe734df8dd826",
+                        "spender": "0x0000000000000000000000000000000000000002",
+                        "value": "1000000000000000000", "nonce": 0, "deadline": 4102444800}
+        }"#).unwrap();
+
+        assert_eq!(
+            permit.encode_type("EIP712Domain").unwrap(),
+            "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)"
+        );
+        assert_eq!(
+            permit.type_hash("Permit").unwrap(),
+            hash("6e71edae12b1b97f4d1f60370fef10105fa2faae0126114a169c64845d6126c9")
+        );
+
+        // The digest built by hand from its EIP-712 definition, signed by the key
+        // `keccak256("cow")`, which owns the permit.
+        let word = |n: u64| H256::from(U256::from(n));
+        let concat = |words: &[H256]| words.iter().flat_map(|w| w.0.to_vec()).collect::<Vec<u8>>();
+        let domain_separator = keccak256(&concat(&[
+            keccak256(b"EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)"),
+            keccak256(b"Dai Stablecoin"),
+            keccak256(b"1"),
+            word(1),
+            H256::from(H160::from_str("6b175474e89094c44da98b954eedeac495271d0f").unwrap())
+        ]));
+        let permit_hash = keccak256(&concat(&[
+            keccak256(b"Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)"),
+            H256::from(H160::from_str(COW).unwrap()),
+            word(2),
+            word(1_000_000_000_000_000_000),
+            word(0),
+            word(4_102_444_800)
+        ]));
+        let digest = keccak256(&[&b"\x19\x01"[..], &domain_separator.0, &permit_hash.0].concat());
+
+        assert_eq!(permit.domain_separator().unwrap(), domain_separator);
+        assert_eq!(permit.signing_hash().unwrap(), digest);
+
+        let key: Scalar = SecretKey::parse(&keccak256(b"cow").0).unwrap().into();
+        let nonce: Scalar = SecretKey::parse(&keccak256(b"nonce").0).unwrap().into();
+        let (r, s, v) = ECMULT_GEN_CONTEXT.sign_raw(&key, &Message::parse(&digest.0).0, &nonce).unwrap();
+        let signature = Signature::new(27 + v, H256::from(&r.b32()[..]), H256::from(&s.b32()[..]));
+        assert_eq!(permit.recover_signer(&signature).unwrap(), H160::from_str(COW).unwrap());
+    }
+
+    #[test]
+    fn rejects_invalid_members() {
+        let mut bad_wallet = mail();
+        bad_wallet.message["from"]["wallet"] = json_str("0x1234");
+        assert!(bad_wallet.signing_hash().is_err());
+
+        let mut missing_contents = mail();
+        missing_contents.message.as_object_mut().unwrap().remove("contents");
+        assert!(missing_contents.signing_hash().is_err());
+    }
+
+    fn json_str(s: &str) -> ::serde_json::Value {
+        ::serde_json::Value::String(s.to_string())
+    }
+}
@@ -1,5 +1,6 @@
 use std::str::FromStr;
 
+use contracts::abi::Contract;
 use types::{H160, H256, U256};
 
 lazy_static! {
@@ -11,57 +12,114 @@ lazy_static! {
         H160::from_str("8d12a197cb00d4747a1fe03395095ce2a5cc6819").unwrap()
     };
 
-    pub static ref ETHERDELTA_CANCEL_ORDER_TOPIC: H256 = {
-        H256::from_str("1e0b760c386003e9cb9bcf4fcf3997886042859d9b6ed6320e804597fcdb28b0").unwrap()
+    /// The EtherDelta contract's ABI.  Method ids and topics below are derived from it.
+    pub static ref ETHERDELTA_ABI: Contract = {
+        Contract::load(include_str!("abi.json")).expect("Invalid EtherDelta ABI")
     };
 
-    pub static ref ETHERDELTA_TRADE_TOPIC: H256 = {
-        H256::from_str("6effdda786735d5033bfad5f53e5131abcced9e52be6c507b62d639685fbed6d").unwrap()
-    };
+    pub static ref ETHERDELTA_CANCEL_ORDER_TOPIC: H256 = event_topic("Cancel");
+    pub static ref ETHERDELTA_ORDER_TOPIC: H256 = event_topic("Order");
+    pub static ref ETHERDELTA_TRADE_TOPIC: H256 = event_topic("Trade");
+    pub static ref ETHERDELTA_DEPOSIT_TOPIC: H256 = event_topic("Deposit");
+    pub static ref ETHERDELTA_WITHDRAW_TOPIC: H256 = event_topic("Withdraw");
 
-    pub static ref ETHERDELTA_DEPOSIT_TOPIC: H256 = {
-        H256::from_str("dcbc1c05240f31ff3ad067ef1ee35ce4997762752e3a095284754544f4c709d7").unwrap()
-    };
+    // Methods
+    pub static ref CANCEL_ORDER_ID: String = method_id("cancelOrder");
+    pub static ref DEPOSIT_ID: String = method_id("deposit");
+    pub static ref DEPOSIT_TOKEN_ID: String = method_id("depositToken");
+    pub static ref ORDER_ID: String = method_id("order");
+    pub static ref TRADE_ID: String = method_id("trade");
+    pub static ref WITHDRAW_ID: String = method_id("withdraw");
+    pub static ref WITHDRAW_TOKEN_ID: String = method_id("withdrawToken");
 
-    pub static ref ETHERDELTA_WITHDRAW_TOPIC: H256 = {
-        H256::from_str("f341246adaac6f497bc2a656f546ab9e182111d630394f0c57c710a59a2cb567").unwrap()
-    };
+    // Static methods
+    pub static ref AMOUNT_FILLED_ID: String = method_id("amountFilled");
+    pub static ref AVAILABLE_VOLUME_ID: String = method_id("availableVolume");
+    pub static ref TEST_TRADE_ID: String = method_id("testTrade");
+    pub static ref BALANCE_OF_ID: String = method_id("balanceOf");
+
+    pub static ref ETHERDELTA_CANCEL_LOG: String = format!("0x{:?}", *ETHERDELTA_CANCEL_ORDER_TOPIC);
+    pub static ref ETHERDELTA_DEPOSIT_LOG: String = format!("0x{:?}", *ETHERDELTA_DEPOSIT_TOPIC);
+    pub static ref ETHERDELTA_ORDER_LOG: String = format!("0x{:?}", *ETHERDELTA_ORDER_TOPIC);
+    pub static ref ETHERDELTA_TRADE_LOG: String = format!("0x{:?}", *ETHERDELTA_TRADE_TOPIC);
+    pub static ref ETHERDELTA_WITHDRAW_LOG: String = format!("0x{:?}", *ETHERDELTA_WITHDRAW_TOPIC);
 }
 
 pub const ETHERDELTA_CREATION_BLOCK: u64 = 3154196;
 
-// Methods
-pub const CANCEL_ORDER_ID: &'static str = "0x278b8c0e";
-pub const DEPOSIT_ID: &'static str = "0xd0e30db0";
-pub const DEPOSIT_TOKEN_ID: &'static str = "0x338b5dea";
-pub const TRADE_ID: &'static str = "0x0a19b14a";
-pub const WITHDRAW_ID: &'static str = "0x2e1a7d4d";
-pub const WITHDRAW_TOKEN_ID: &'static str = "0x9e281a98";
-
-// Static methods
-pub const AMOUNT_FILLED_ID: &'static str = "0x46be96c3";
-pub const AVAILABLE_VOLUME_ID: &'static str = "0xfb6e155f";
-pub const TEST_TRADE_ID: &'static str = "0x6c86888b";
-pub const BALANCE_OF_ID: &'static str = "0xf7888aec";
-
+fn method_id(name: &str) -> String {
+    ETHERDELTA_ABI.function(name)
+        .map(|f| f.method_id())
+        .unwrap_or_else(|| panic!("EtherDelta ABI is missing function {}", name))
+}
 
-pub const ETHERDELTA_CANCEL_LOG: &'static str = "0x1e0b760c386003e9cb9bcf4fcf3997886042859d9b6ed6320e804597fcdb28b0";
-pub const ETHERDELTA_DEPOSIT_LOG: &'static str = "0xdcbc1c05240f31ff3ad067ef1ee35ce4997762752e3a095284754544f4c709d7";
-pub const ETHERDELTA_TRADE_LOG: &'static str = "0x6effdda786735d5033bfad5f53e5131abcced9e52be6c507b62d639685fbed6d";
-pub const ETHERDELTA_WITHDRAW_LOG: &'static str = "0xf341246adaac6f497bc2a656f546ab9e182111d630394f0c57c710a59a2cb567";
+fn event_topic(name: &str) -> H256 {
+    ETHERDELTA_ABI.event(name)
+        .map(|e| e.topic())
+        .unwrap_or_else(|| panic!("EtherDelta ABI is missing event {}", name))
+}
 
 #[cfg(test)]
 mod tests {
+    use std::str::FromStr;
+
+    use types::H256;
     use super::*;
 
     #[test]
     fn statics_initialize() {
-        let maker = &MAKER_FEE;
-        let taker = &TAKER_FEE;
-        let address = &ETHERDELTA_ADDRESS;
-        let topic1 = &ETHERDELTA_CANCEL_ORDER_TOPIC;
-        let topic2 = &ETHERDELTA_TRADE_TOPIC;
-        let topic3 = &ETHERDELTA_DEPOSIT_TOPIC;
-        let topic4 = &ETHERDELTA_WITHDRAW_TOPIC;
+        let _maker = &MAKER_FEE;
+        let _taker = &TAKER_FEE;
+        let _address = &ETHERDELTA_ADDRESS;
+        let _topic1 = &ETHERDELTA_CANCEL_ORDER_TOPIC;
+        let _topic2 = &ETHERDELTA_TRADE_TOPIC;
+        let _topic3 = &ETHERDELTA_DEPOSIT_TOPIC;
+        let _topic4 = &ETHERDELTA_WITHDRAW_TOPIC;
+        let _topic5 = &ETHERDELTA_ORDER_TOPIC;
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn method_ids_match_deployed_contract() {
+        assert_eq!(*CANCEL_ORDER_ID, "0x278b8c0e");
+        assert_eq!(*DEPOSIT_ID, "0xd0e30db0");
+        assert_eq!(*DEPOSIT_TOKEN_ID, "0x338b5dea");
+        assert_eq!(*ORDER_ID, "0x0b927666");
+        assert_eq!(*TRADE_ID, "0x0a19b14a");
+        assert_eq!(*WITHDRAW_ID, "0x2e1a7d4d");
+        assert_eq!(*WITHDRAW_TOKEN_ID, "0x9e281a98");
+        assert_eq!(*AMOUNT_FILLED_ID, "0x46be96c3");
+        assert_eq!(*AVAILABLE_VOLUME_ID, "0xfb6e155f");
+        assert_eq!(*TEST_TRADE_ID, "0x6c86888b");
+        assert_eq!(*BALANCE_OF_ID, "0xf7888aec");
+    }
+
+    #[test]
+    fn topics_match_deployed_contract() {
+        let topic = |s: &str| H256::from_str(s).unwrap();
+
+        assert_eq!(
+            *ETHERDELTA_CANCEL_ORDER_TOPIC,
+            topic("1e0b760c386003e9cb9bcf4fcf3997886042859d9b6ed6320e804597fcdb28b0")
+        );
+        assert_eq!(
+            *ETHERDELTA_ORDER_TOPIC,
+            topic("3f7f2eda73683c21a15f9435af1028c93185b5f1fa38270762dc32be606b3e85")
+        );
+        assert_eq!(
+            *ETHERDELTA_TRADE_TOPIC,
+            topic("6effdda786735d5033bfad5f53e5131abcced9e52be6c507b62d639685fbed6d")
+        );
+        assert_eq!(
+            *ETHERDELTA_DEPOSIT_TOPIC,
+            topic("dcbc1c05240f31ff3ad067ef1ee35ce4997762752e3a095284754544f4c709d7")
+        );
+        assert_eq!(
+            *ETHERDELTA_WITHDRAW_TOPIC,
+            topic("f341246adaac6f497bc2a656f546ab9e182111d630394f0c57c710a59a2cb567")
+        );
+        assert_eq!(
+            *ETHERDELTA_TRADE_LOG,
+            "0x6effdda786735d5033bfad5f53e5131abcced9e52be6c507b62d639685fbed6d"
+        );
+    }
+}
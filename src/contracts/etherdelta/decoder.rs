@@ -1,12 +1,11 @@
-use std::str::{self, FromStr};
+use std::vec;
 
-use fixed_hash::clean_0x;
-use serde_json;
+use rustc_serialize::hex::ToHex;
 
-use contracts::normalize_data;
+use contracts::abi::{take_value, AbiValue, DecodeMode};
 use error::{Error, ErrorKind};
 use objects::{Log, ParityTrace, Transaction};
-use types::{H160, H256, U256};
+use types::{H256, U256};
 
 use super::models::*;
 use super::constants::*;
@@ -21,13 +20,14 @@ impl EtherDeltaDecoder {
             Err(ErrorKind::Decoder("Transaction input length < 10".to_string()).into())
         } else {
             let method_id = &input[..10];
-            match &*method_id {
-                CANCEL_ORDER_ID => EtherDeltaDecoder::decode_cancel_order(&tx),
-                DEPOSIT_ID => Ok(EtherDeltaAction::Deposit(tx.value.clone())),
-                DEPOSIT_TOKEN_ID => EtherDeltaDecoder::decode_deposit_token(&tx),
-                TRADE_ID => EtherDeltaDecoder::decode_trade(&tx),
-                WITHDRAW_ID => EtherDeltaDecoder::decode_withdraw(&tx),
-                WITHDRAW_TOKEN_ID => EtherDeltaDecoder::decode_withdraw_token(&tx),
+            match method_id {
+                id if id == *CANCEL_ORDER_ID => EtherDeltaDecoder::decode_cancel_order(tx),
+                id if id == *DEPOSIT_ID => Ok(EtherDeltaAction::Deposit(tx.value)),
+                id if id == *DEPOSIT_TOKEN_ID => EtherDeltaDecoder::decode_deposit_token(tx),
+                id if id == *ORDER_ID => EtherDeltaDecoder::decode_order(tx),
+                id if id == *TRADE_ID => EtherDeltaDecoder::decode_trade(tx),
+                id if id == *WITHDRAW_ID => EtherDeltaDecoder::decode_withdraw(tx),
+                id if id == *WITHDRAW_TOKEN_ID => EtherDeltaDecoder::decode_withdraw_token(tx),
                 _ => Err(ErrorKind::Decoder(format!("Invalid method: {}", &method_id)).into())
             }
         }
@@ -39,17 +39,18 @@ impl EtherDeltaDecoder {
             Err(ErrorKind::Decoder("Trace action input length < 10".to_string()).into())
         } else {
             let method_id = &input[..10];
-            match &*method_id {
-                CANCEL_ORDER_ID => EtherDeltaDecoder::decode_cancel_order_id(&input),
-                DEPOSIT_ID => Ok(EtherDeltaAction::Deposit(trace.action.value.unwrap().clone())),
-                DEPOSIT_TOKEN_ID => EtherDeltaDecoder::decode_deposit_token_id(&input),
-                TRADE_ID => EtherDeltaDecoder::decode_trade_id(&input),
-                WITHDRAW_ID => EtherDeltaDecoder::decode_withdraw_id(&input),
-                WITHDRAW_TOKEN_ID => EtherDeltaDecoder::decode_withdraw_token_id(&input),
-                AMOUNT_FILLED_ID => EtherDeltaDecoder::decode_amount_filled(&trace),
-                AVAILABLE_VOLUME_ID => EtherDeltaDecoder::decode_available_volume(&trace),
-                TEST_TRADE_ID => EtherDeltaDecoder::decode_test_trade(&trace),
-                BALANCE_OF_ID => EtherDeltaDecoder::decode_balance_of(&trace),
+            match method_id {
+                id if id == *CANCEL_ORDER_ID => EtherDeltaDecoder::decode_cancel_order_id(input),
+                id if id == *DEPOSIT_ID => Ok(EtherDeltaAction::Deposit(trace.action.value.unwrap_or_default())),
+                id if id == *DEPOSIT_TOKEN_ID => EtherDeltaDecoder::decode_deposit_token_id(input),
+                id if id == *ORDER_ID => EtherDeltaDecoder::decode_order_id(input),
+                id if id == *TRADE_ID => EtherDeltaDecoder::decode_trade_id(input),
+                id if id == *WITHDRAW_ID => EtherDeltaDecoder::decode_withdraw_id(input),
+                id if id == *WITHDRAW_TOKEN_ID => EtherDeltaDecoder::decode_withdraw_token_id(input),
+                id if id == *AMOUNT_FILLED_ID => EtherDeltaDecoder::decode_amount_filled(trace),
+                id if id == *AVAILABLE_VOLUME_ID => EtherDeltaDecoder::decode_available_volume(trace),
+                id if id == *TEST_TRADE_ID => EtherDeltaDecoder::decode_test_trade(trace),
+                id if id == *BALANCE_OF_ID => EtherDeltaDecoder::decode_balance_of(trace),
                 _ => Err(ErrorKind::Decoder(
                     format!("[Tx: {:?}] Unknown trace input: {}", &trace.transaction_hash, &method_id)
                 ).into())
@@ -58,130 +59,93 @@ impl EtherDeltaDecoder {
     }
 
     pub fn decode_event(log: &Log) -> Result<EtherDeltaEvent, Error> {
-        log.topics.get(0)
+        log.topics.first()
             .ok_or(ErrorKind::Decoder("Expected log topic vec of length (1)".to_string()).into())
             .and_then(|topic| {
-                serde_json::to_string(&topic)
-                    .map_err(|e| ErrorKind::Decoder("Couldn't decode log topic".to_string()).into())
-                    .and_then(|hash| {
-                        let h = hash.replace(r#"""#, "");
-                        match h.as_ref() {
-                            ETHERDELTA_CANCEL_LOG => EtherDeltaDecoder::decode_cancel_log(&log),
-                            ETHERDELTA_TRADE_LOG => EtherDeltaDecoder::decode_trade_log(&log),
-                            ETHERDELTA_DEPOSIT_LOG => EtherDeltaDecoder::decode_transfer_log(&topic, &log),
-                            ETHERDELTA_WITHDRAW_LOG => EtherDeltaDecoder::decode_transfer_log(&topic, &log),
-                            _ => Err(ErrorKind::Decoder(format!("Invalid log topic: {:?}", &topic)).into())
-                        }
-                    })
+                match *topic {
+                    t if t == *ETHERDELTA_CANCEL_ORDER_TOPIC => EtherDeltaDecoder::decode_cancel_log(log),
+                    t if t == *ETHERDELTA_ORDER_TOPIC => EtherDeltaDecoder::decode_order_log(log),
+                    t if t == *ETHERDELTA_TRADE_TOPIC => EtherDeltaDecoder::decode_trade_log(log),
+                    t if t == *ETHERDELTA_DEPOSIT_TOPIC => EtherDeltaDecoder::decode_transfer_log(topic, log),
+                    t if t == *ETHERDELTA_WITHDRAW_TOPIC => EtherDeltaDecoder::decode_transfer_log(topic, log),
+                    _ => Err(ErrorKind::Decoder(format!("Invalid log topic: {:?}", &topic)).into())
+                }
             })
     }
 
+    /// Decodes the arguments of a call to the named function of the EtherDelta ABI.
+    fn decode_call(name: &str, input: &str) -> Result<vec::IntoIter<AbiValue>, Error> {
+        ETHERDELTA_ABI.function(name)
+            .ok_or_else(|| ErrorKind::Decoder(format!("Unknown function: {}", name)).into())
+            .and_then(|function| function.decode_input(input, DecodeMode::Strict))
+            .map(|args| args.into_iter())
+    }
+
     fn decode_cancel_log(log: &Log) -> Result<EtherDeltaEvent, Error> {
-        let data = log.data.to_owned();
-        let data = clean_0x(&data);
-        let expected_length = 64 * 10;
-        if data.len() != expected_length {
-            Err(ErrorKind::Decoder(format!("Couldn't decode cancel log: {}", &data)).into())
-        } else {
-            let fields = data.as_bytes()
-                .chunks(64 as usize)
-                .map(|buf| unsafe { str::from_utf8_unchecked(buf).to_string() })
-                .collect::<Vec<String>>();
-
-            Ok(EtherDeltaEvent::Cancel(
-                OrderData {
-                    token_get: H160::from_str(&fields[0][24..]).unwrap(),
-                    amount_get: U256::from_str(&fields[1]).unwrap(),
-                    token_give: H160::from_str(&fields[2][24..]).unwrap(),
-                    amount_give: U256::from_str(&fields[3]).unwrap(),
-                    expires: U256::from_str(&fields[4]).unwrap(),
-                    nonce: U256::from_str(&fields[5]).unwrap(),
-                    v: U256::from_str(&fields[7]).unwrap(),
-                    r: fields[8].to_string(),
-                    s: fields[9].to_string()
-                },
-                H160::from_str(&fields[6][24..]).unwrap()
-            ))
-        }
+        let fields = ETHERDELTA_ABI.decode_log("Cancel", log)?;
+
+        Ok(EtherDeltaEvent::Cancel(
+            OrderData {
+                token_get: fields.address("tokenGet")?,
+                amount_get: fields.uint("amountGet")?,
+                token_give: fields.address("tokenGive")?,
+                amount_give: fields.uint("amountGive")?,
+                expires: fields.uint("expires")?,
+                nonce: fields.uint("nonce")?,
+                v: fields.uint("v")?,
+                r: fields.hex("r")?,
+                s: fields.hex("s")?
+            },
+            fields.address("user")?
+        ))
     }
 
     fn decode_transfer_log(topic: &H256, log: &Log) -> Result<EtherDeltaEvent, Error> {
-        let data = log.data.to_owned();
-        let data = clean_0x(&data);
-        let expected_length = 64 * 4;
-        if data.len() != expected_length {
-            Err(ErrorKind::Decoder(format!("Couldn't decode transfer log: {}", &data)).into())
+        let is_deposit = *topic == *ETHERDELTA_DEPOSIT_TOPIC;
+        let fields = ETHERDELTA_ABI.decode_log(if is_deposit { "Deposit" } else { "Withdraw" }, log)?;
+
+        let token = fields.address("token")?;
+        let user = fields.address("user")?;
+        let amount = fields.uint("amount")?;
+        let balance = fields.uint("balance")?;
+
+        if is_deposit {
+            Ok(EtherDeltaEvent::Deposit(token, user, amount, balance))
         } else {
-            let fields = data.as_bytes()
-                .chunks(64 as usize)
-                .map(|buf| unsafe { str::from_utf8_unchecked(buf).to_string() })
-                .collect::<Vec<String>>();
-
-            let token = H160::from_str(&fields[0][24..]).unwrap();
-            let user = H160::from_str(&fields[1][24..]).unwrap();
-            let amount = U256::from_str(&fields[2]).unwrap();
-            let balance = U256::from_str(&fields[3]).unwrap();
-
-            if topic == &*ETHERDELTA_DEPOSIT_TOPIC {
-                Ok(EtherDeltaEvent::Deposit(token, user, amount, balance))
-            } else if topic == &*ETHERDELTA_WITHDRAW_TOPIC {
-                Ok(EtherDeltaEvent::Withdraw(token, user, amount, balance))
-            } else {
-                Err(ErrorKind::Decoder(format!("Couldn't decode transfer log: {}", &data)).into())
-            }
+            Ok(EtherDeltaEvent::Withdraw(token, user, amount, balance))
         }
     }
 
     fn decode_order_log(log: &Log) -> Result<EtherDeltaEvent, Error> {
-        let data = log.data.to_owned();
-        let data = clean_0x(&data);
-        let expected_length = 64 * 7;
-        if data.len() != expected_length {
-            Err(ErrorKind::Decoder(format!("Couldn't decode order log: {}", &data)).into())
-        } else {
-            let fields = data.as_bytes()
-                .chunks(64 as usize)
-                .map(|buf| unsafe { str::from_utf8_unchecked(buf).to_string() })
-                .collect::<Vec<String>>();
-
-            Ok(EtherDeltaEvent::Order(
-                OrderLog {
-                    token_get: H160::from_str(&fields[0][24..]).unwrap(),
-                    amount_get: U256::from_str(&fields[1]).unwrap(),
-                    token_give: H160::from_str(&fields[2][24..]).unwrap(),
-                    amount_give: U256::from_str(&fields[3]).unwrap(),
-                    expires: U256::from_str(&fields[4]).unwrap(),
-                    nonce: U256::from_str(&fields[5]).unwrap(),
-                    user: H160::from_str(&fields[6][24..]).unwrap()
-                }
-            ))
-        }
+        let fields = ETHERDELTA_ABI.decode_log("Order", log)?;
+
+        Ok(EtherDeltaEvent::Order(
+            OrderLog {
+                token_get: fields.address("tokenGet")?,
+                amount_get: fields.uint("amountGet")?,
+                token_give: fields.address("tokenGive")?,
+                amount_give: fields.uint("amountGive")?,
+                expires: fields.uint("expires")?,
+                nonce: fields.uint("nonce")?,
+                user: fields.address("user")?
+            }
+        ))
     }
 
     fn decode_trade_log(log: &Log) -> Result<EtherDeltaEvent, Error> {
-        let data = log.data.to_owned();
-        let data = clean_0x(&data);
-        let expected_length = 64 * 6;
-        if data.len() != expected_length {
-            Err(ErrorKind::Decoder(format!("Couldn't decode trade log: {}", &data)).into())
-        } else {
-            let fields = data.as_bytes()
-                .chunks(64 as usize)
-                .map(|buf| unsafe { str::from_utf8_unchecked(buf).to_string() })
-                .collect::<Vec<String>>();
-
-            Ok(EtherDeltaEvent::Trade(
-                TradeLog {
-                    token_get: H160::from_str(&fields[0][24..]).unwrap(),
-                    amount_get: U256::from_str(&fields[1]).unwrap(),
-                    token_give: H160::from_str(&fields[2][24..]).unwrap(),
-                    amount_give: U256::from_str(&fields[3]).unwrap(),
-                    maker: H160::from_str(&fields[4][24..]).unwrap(),
-                    taker: H160::from_str(&fields[5][24..]).unwrap(),
-                    price: 0.0
-                }
-            ))
-        }
+        let fields = ETHERDELTA_ABI.decode_log("Trade", log)?;
+
+        Ok(EtherDeltaEvent::Trade(
+            TradeLog {
+                token_get: fields.address("tokenGet")?,
+                amount_get: fields.uint("amountGet")?,
+                token_give: fields.address("tokenGive")?,
+                amount_give: fields.uint("amountGive")?,
+                maker: fields.address("get")?,
+                taker: fields.address("give")?,
+                price: None
+            }
+        ))
     }
 
     fn decode_cancel_order(tx: &Transaction) -> Result<EtherDeltaAction, Error> {
@@ -189,24 +153,23 @@ impl EtherDeltaDecoder {
     }
 
     fn decode_cancel_order_id(input: &str) -> Result<EtherDeltaAction, Error> {
-        match normalize_data(&input, 9) {
-            Ok(fields) => {
-                Ok(EtherDeltaAction::CancelOrder(
-                    OrderData {
-                        token_get: H160::from_str(&fields[0][24..]).unwrap(),
-                        amount_get: U256::from_str(&fields[1]).unwrap(),
-                        token_give: H160::from_str(&fields[2][24..]).unwrap(),
-                        amount_give: U256::from_str(&fields[3]).unwrap(),
-                        expires: U256::from_str(&fields[4]).unwrap(),
-                        nonce: U256::from_str(&fields[5]).unwrap(),
-                        v: U256::from_str(&fields[6]).unwrap(),
-                        r: fields[7].to_string(),
-                        s: fields[8].to_string()
-                    }
-                ))
-            },
-            Err(e) => Err(e)
-        }
+        let mut args = EtherDeltaDecoder::decode_call("cancelOrder", input)?;
+        let mut order = take_order(&mut args)?;
+        take_signature(&mut args, &mut order)?;
+
+        Ok(EtherDeltaAction::CancelOrder(order))
+    }
+
+    fn decode_order(tx: &Transaction) -> Result<EtherDeltaAction, Error> {
+        EtherDeltaDecoder::decode_order_id(&tx.input)
+    }
+
+    /// On-chain orders aren't signed: the contract stores them for the sender instead, so
+    /// `v`, `r` and `s` are zero, as in the trades that fill them.
+    fn decode_order_id(input: &str) -> Result<EtherDeltaAction, Error> {
+        let mut args = EtherDeltaDecoder::decode_call("order", input)?;
+
+        Ok(EtherDeltaAction::Order(take_order(&mut args)?))
     }
 
     fn decode_withdraw(tx: &Transaction) -> Result<EtherDeltaAction, Error> {
@@ -214,10 +177,9 @@ impl EtherDeltaDecoder {
     }
 
     fn decode_withdraw_id(input: &str) -> Result<EtherDeltaAction, Error> {
-        match normalize_data(&input, 1) {
-            Ok(fields) => Ok(EtherDeltaAction::Withdraw(U256::from_str(&fields[0]).unwrap())),
-            Err(e) => Err(e)
-        }
+        let mut args = EtherDeltaDecoder::decode_call("withdraw", input)?;
+
+        Ok(EtherDeltaAction::Withdraw(take_value(&mut args)?))
     }
 
     fn decode_deposit_token(tx: &Transaction) -> Result<EtherDeltaAction, Error> {
@@ -225,15 +187,9 @@ impl EtherDeltaDecoder {
     }
 
     fn decode_deposit_token_id(input: &str) -> Result<EtherDeltaAction, Error> {
-        match normalize_data(&input, 2) {
-            Ok(fields) => {
-                Ok(EtherDeltaAction::DepositToken(
-                    H160::from_str(&fields[0][24..]).unwrap(),
-                    U256::from_str(&fields[1]).unwrap()
-                ))
-            },
-            Err(e) => Err(e)
-        }
+        let mut args = EtherDeltaDecoder::decode_call("depositToken", input)?;
+
+        Ok(EtherDeltaAction::DepositToken(take_value(&mut args)?, take_value(&mut args)?))
     }
 
     fn decode_withdraw_token(tx: &Transaction) -> Result<EtherDeltaAction, Error> {
@@ -241,15 +197,9 @@ impl EtherDeltaDecoder {
     }
 
     fn decode_withdraw_token_id(input: &str) -> Result<EtherDeltaAction, Error> {
-        match normalize_data(&input, 2) {
-            Ok(fields) => {
-                Ok(EtherDeltaAction::WithdrawToken(
-                    H160::from_str(&fields[0][24..]).unwrap(),
-                    U256::from_str(&fields[1]).unwrap()
-                ))
-            },
-            Err(e) => Err(e)
-        }
+        let mut args = EtherDeltaDecoder::decode_call("withdrawToken", input)?;
+
+        Ok(EtherDeltaAction::WithdrawToken(take_value(&mut args)?, take_value(&mut args)?))
     }
 
     fn decode_trade(tx: &Transaction) -> Result<EtherDeltaAction, Error> {
@@ -257,95 +207,221 @@ impl EtherDeltaDecoder {
     }
 
     fn decode_trade_id(input: &str) -> Result<EtherDeltaAction, Error> {
-        match normalize_data(&input, 11) {
-            Ok(fields) => {
-                Ok(EtherDeltaAction::Trade(
-                    OrderData {
-                        token_get: H160::from_str(&fields[0][24..]).unwrap(),
-                        amount_get: U256::from_str(&fields[1]).unwrap(),
-                        token_give: H160::from_str(&fields[2][24..]).unwrap(),
-                        amount_give: U256::from_str(&fields[3]).unwrap(),
-                        expires: U256::from_str(&fields[4]).unwrap(),
-                        nonce: U256::from_str(&fields[5]).unwrap(),
-                        v: U256::from_str(&fields[7]).unwrap(),
-                        r: fields[8].to_string(),
-                        s: fields[9].to_string(),
-                    },
-                    H160::from_str(&fields[6][24..]).unwrap(),
-                    U256::from_str(&fields[10]).unwrap()
-                ))
-            },
-            Err(e) => Err(e)
-        }
+        let mut args = EtherDeltaDecoder::decode_call("trade", input)?;
+        let mut order = take_order(&mut args)?;
+        let user = take_value(&mut args)?;
+        take_signature(&mut args, &mut order)?;
+
+        Ok(EtherDeltaAction::Trade(order, user, take_value(&mut args)?))
     }
 
     fn decode_amount_filled(trace: &ParityTrace) -> Result<EtherDeltaAction, Error> {
-        match normalize_data(&trace.action.input, 10) {
-            Ok(fields) => {
-                Ok(EtherDeltaAction::AmountFilled(
-                    OrderData {
-                        token_get: H160::from_str(&fields[0][24..]).unwrap(),
-                        amount_get: U256::from_str(&fields[1]).unwrap(),
-                        token_give: H160::from_str(&fields[2][24..]).unwrap(),
-                        amount_give: U256::from_str(&fields[3]).unwrap(),
-                        expires: U256::from_str(&fields[4]).unwrap(),
-                        nonce: U256::from_str(&fields[5]).unwrap(),
-                        v: U256::from_str(&fields[7]).unwrap(),
-                        r: fields[8].to_string(),
-                        s: fields[9].to_string(),
-                    },
-                    H160::from_str(&fields[6][24..]).unwrap()
-                ))
-            },
-            Err(e) => Err(e)
-        }
+        let mut args = EtherDeltaDecoder::decode_call("amountFilled", &trace.action.input)?;
+        let mut order = take_order(&mut args)?;
+        let user = take_value(&mut args)?;
+        take_signature(&mut args, &mut order)?;
+
+        Ok(EtherDeltaAction::AmountFilled(order, user))
     }
 
     fn decode_available_volume(trace: &ParityTrace) -> Result<EtherDeltaAction, Error> {
-        match EtherDeltaDecoder::decode_amount_filled(&trace) {
-            Ok(EtherDeltaAction::AmountFilled(data, user)) => {
-                Ok(EtherDeltaAction::AvailableVolume(data, user))
+        let mut args = EtherDeltaDecoder::decode_call("availableVolume", &trace.action.input)?;
+        let mut order = take_order(&mut args)?;
+        let user = take_value(&mut args)?;
+        take_signature(&mut args, &mut order)?;
+
+        Ok(EtherDeltaAction::AvailableVolume(order, user))
+    }
+
+    fn decode_test_trade(trace: &ParityTrace) -> Result<EtherDeltaAction, Error> {
+        let mut args = EtherDeltaDecoder::decode_call("testTrade", &trace.action.input)?;
+        let mut order = take_order(&mut args)?;
+        let user = take_value(&mut args)?;
+        take_signature(&mut args, &mut order)?;
+
+        Ok(EtherDeltaAction::TestTrade(order, user, take_value(&mut args)?, take_value(&mut args)?))
+    }
+
+    fn decode_balance_of(trace: &ParityTrace) -> Result<EtherDeltaAction, Error> {
+        let mut args = EtherDeltaDecoder::decode_call("balanceOf", &trace.action.input)?;
+
+        Ok(EtherDeltaAction::BalanceOf(take_value(&mut args)?, take_value(&mut args)?))
+    }
+}
+
+/// Takes the terms every EtherDelta function starts with, from `tokenGet` to `nonce`.  The
+/// order is left unsigned.
+fn take_order<I: Iterator<Item = AbiValue>>(args: &mut I) -> Result<OrderData, Error> {
+    Ok(OrderData {
+        token_get: take_value(args)?,
+        amount_get: take_value(args)?,
+        token_give: take_value(args)?,
+        amount_give: take_value(args)?,
+        expires: take_value(args)?,
+        nonce: take_value(args)?,
+        v: U256::zero(),
+        r: format!("{:064x}", 0),
+        s: format!("{:064x}", 0)
+    })
+}
+
+/// Takes the `v`, `r` and `s` of the order's signature.
+fn take_signature<I: Iterator<Item = AbiValue>>(args: &mut I, order: &mut OrderData) -> Result<(), Error> {
+    order.v = take_value(args)?;
+    order.r = take_value::<Vec<u8>, _>(args)?.to_hex();
+    order.s = take_value::<Vec<u8>, _>(args)?.to_hex();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use serde_json;
+
+    use objects::fixtures::{log, proxy_traces};
+    use objects::{Log, Transaction};
+    use types::{H160, H256, U256};
+    use super::EtherDeltaDecoder;
+    use super::super::constants::*;
+    use super::super::models::{EtherDeltaAction, EtherDeltaEvent, OrderData};
+
+    #[test]
+    fn decodes_trade_events() {
+        let zrx = "e41d2489571d322189246dafa5ebde1f4699f498";
+        let maker = "461680c41786ec889ac9de7f5e89a2f4e0fcb156";
+        let taker = "000029c8dc1f3f6c6e4b6ef7c6a56c8e45e9e9c7";
+        let data = format!(
+            "0x{:0>64}{:064x}{:0>64}{:064x}{:0>64}{:0>64}",
+            zrx, 1000, "0", 25, maker, taker
+        );
+
+        let trade_log = log(*ETHERDELTA_ADDRESS, vec![*ETHERDELTA_TRADE_TOPIC], &data);
+
+        match EtherDeltaDecoder::decode_event(&trade_log).unwrap() {
+            EtherDeltaEvent::Trade(trade) => {
+                assert_eq!(trade.token_get, H160::from_str(zrx).unwrap());
+                assert_eq!(trade.amount_get, U256::from(1000));
+                assert_eq!(trade.token_give, H160::zero());
+                assert_eq!(trade.amount_give, U256::from(25));
+                assert_eq!(trade.maker, H160::from_str(maker).unwrap());
+                assert_eq!(trade.taker, H160::from_str(taker).unwrap());
             },
-            Err(e) => Err(e),
-            _ => Err(ErrorKind::Decoder(format!("Invalid input: {}", &trace.action.input)).into())
+            other => panic!("Expected a trade, got {:?}", other)
         }
     }
 
-    fn decode_test_trade(trace: &ParityTrace) -> Result<EtherDeltaAction, Error> {
-        match normalize_data(&trace.action.input, 12) {
-            Ok(fields) => {
-                Ok(EtherDeltaAction::TestTrade(
-                    OrderData {
-                        token_get: H160::from_str(&fields[0][24..]).unwrap(),
-                        amount_get: U256::from_str(&fields[1]).unwrap(),
-                        token_give: H160::from_str(&fields[2][24..]).unwrap(),
-                        amount_give: U256::from_str(&fields[3]).unwrap(),
-                        expires: U256::from_str(&fields[4]).unwrap(),
-                        nonce: U256::from_str(&fields[5]).unwrap(),
-                        v: U256::from_str(&fields[7]).unwrap(),
-                        r: fields[8].to_string(),
-                        s: fields[9].to_string(),
-                    },
-                    H160::from_str(&fields[6][24..]).unwrap(),
-                    U256::from_str(&fields[10]).unwrap(),
-                    H160::from_str(&fields[11][24..]).unwrap()
-                ))
+    /// The first order filled through the proxy in `etherdelta_proxy_trace.json`, and its maker.
+    fn proxy_order() -> (OrderData, H160) {
+        proxy_traces().iter()
+            .filter_map(|trace| match EtherDeltaDecoder::decode_trace_action(trace) {
+                Ok(EtherDeltaAction::Trade(order, maker, _)) => Some((order, maker)),
+                _ => None
+            })
+            .next()
+            .unwrap()
+    }
+
+    /// A synthetic `order` call (`test_data/etherdelta_synthetic_order_tx.json`) and `Order`
+    /// log (`test_data/etherdelta_synthetic_order_log.json`), built from the order filled first
+    /// in the proxy transaction and its maker.  Neither was mined: the transaction has no hash
+    /// or block.
+    fn placed_order() -> (Transaction, Log) {
+        let tx = include_str!("../../../test_data/etherdelta_synthetic_order_tx.json");
+        let log = include_str!("../../../test_data/etherdelta_synthetic_order_log.json");
+        (serde_json::from_str(tx).unwrap(), serde_json::from_str(log).unwrap())
+    }
+
+    fn assert_placed(order: &OrderData) {
+        assert_eq!(order.token_get, H160::zero());
+        assert_eq!(order.amount_get, U256::from_dec_str("1399960000000000000").unwrap());
+        assert_eq!(order.token_give, H160::from_str("e3818504c1b32bf1557b16c238b2e01fd3149c17").unwrap());
+        assert_eq!(order.amount_give, U256::from_dec_str("4000000000000000000000").unwrap());
+        assert_eq!(order.expires, U256::from(4747193));
+        assert_eq!(order.nonce, U256::from(2971771134u64));
+    }
+
+    #[test]
+    fn decodes_order_events() {
+        let (_, log) = placed_order();
+        let (filled, maker) = proxy_order();
+        assert_eq!(log.topics[0], *ETHERDELTA_ORDER_TOPIC);
+
+        match EtherDeltaDecoder::decode_event(&log).unwrap() {
+            EtherDeltaEvent::Order(placed) => {
+                let order = OrderData {
+                    token_get: placed.token_get,
+                    amount_get: placed.amount_get,
+                    token_give: placed.token_give,
+                    amount_give: placed.amount_give,
+                    expires: placed.expires,
+                    nonce: placed.nonce,
+                    ..filled.clone()
+                };
+                assert_placed(&order);
+                assert_eq!(order.hash_order(), filled.hash_order());
+                assert_eq!(placed.user, maker);
             },
-            Err(e) => Err(e)
+            other => panic!("Expected an order, got {:?}", other)
         }
+
+        let short = Log { data: format!("0x{:064x}", 1), ..log };
+        assert!(EtherDeltaDecoder::decode_event(&short).is_err());
     }
 
-    fn decode_balance_of(trace: &ParityTrace) -> Result<EtherDeltaAction, Error> {
-        match normalize_data(&trace.action.input, 2) {
-            Ok(fields) => {
-                Ok(EtherDeltaAction::BalanceOf(
-                    H160::from_str(&fields[0][24..]).unwrap(),
-                    H160::from_str(&fields[1][24..]).unwrap()
-                ))
+    #[test]
+    fn decodes_order_calls() {
+        let (tx, _) = placed_order();
+        let (filled, maker) = proxy_order();
+        assert_eq!(&tx.input[..10], ORDER_ID.as_str());
+
+        match EtherDeltaDecoder::decode_action(&tx).unwrap() {
+            EtherDeltaAction::Order(placed) => {
+                assert_placed(&placed);
+                assert_eq!(placed.hash_order(), filled.hash_order());
+                assert_eq!((placed.v, placed.r.as_str()), (U256::zero(), "0".repeat(64).as_str()));
+                assert!(!placed.is_signed_by(&maker));
             },
-            Err(e) => Err(e)
+            other => panic!("Expected an order, got {:?}", other)
+        }
+
+        let truncated = Transaction { input: tx.input[..tx.input.len() - 64].to_string(), ..tx.clone() };
+        assert!(EtherDeltaDecoder::decode_action(&truncated).is_err());
+        let unaligned = Transaction { input: format!("{}00", tx.input), ..tx.clone() };
+        assert!(EtherDeltaDecoder::decode_action(&unaligned).is_err());
+        let dirty = Transaction { input: tx.input.replacen("0000", "ffff", 1), ..tx };
+        assert!(EtherDeltaDecoder::decode_action(&dirty).is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_trace_inputs() {
+        let mut trace = proxy_traces().remove(0);
+        let ids = [
+            &*CANCEL_ORDER_ID, &*DEPOSIT_TOKEN_ID, &*ORDER_ID, &*TRADE_ID, &*WITHDRAW_ID, &*WITHDRAW_TOKEN_ID,
+            &*AMOUNT_FILLED_ID, &*AVAILABLE_VOLUME_ID, &*TEST_TRADE_ID, &*BALANCE_OF_ID
+        ];
+        for id in ids.iter() {
+            trace.action.input = format!("{}{:062x}", id, 1);
+            assert!(EtherDeltaDecoder::decode_trace_action(&trace).is_err(), "{}", id);
+            trace.action.input = format!("{}{}", id, "zz".repeat(32 * 12));
+            assert!(EtherDeltaDecoder::decode_trace_action(&trace).is_err(), "{}", id);
+        }
+
+        trace.action.input = DEPOSIT_ID.to_string();
+        trace.action.value = None;
+        match EtherDeltaDecoder::decode_trace_action(&trace).unwrap() {
+            EtherDeltaAction::Deposit(value) => assert!(value.is_zero()),
+            other => panic!("Expected a deposit, got {:?}", other)
         }
     }
 
-}
+    #[test]
+    fn rejects_malformed_events() {
+        let short = format!("0x{:064x}", 1);
 
+        for topic in &[*ETHERDELTA_TRADE_TOPIC, *ETHERDELTA_CANCEL_ORDER_TOPIC] {
+            assert!(EtherDeltaDecoder::decode_event(&log(*ETHERDELTA_ADDRESS, vec![*topic], &short)).is_err());
+        }
+        assert!(EtherDeltaDecoder::decode_event(&log(*ETHERDELTA_ADDRESS, vec![H256::zero()], "0x")).is_err());
+    }
+}
@@ -0,0 +1,111 @@
+use contracts::abi::{hex_to_bytes, AbiValue, DecodeMode, Function};
+use error::{Error, ErrorKind};
+use objects::TransactionCall;
+use types::{H160, U256};
+
+use super::constants::*;
+use super::models::OrderData;
+
+/// Builds `eth_call`s against the EtherDelta smart contract's constant functions, and decodes
+/// their results.
+pub struct EtherDeltaEncoder;
+
+impl EtherDeltaEncoder {
+    /// Balance of `user` in the contract.  The ether balance uses token `0x0...0`.
+    pub fn balance_of(token: H160, user: H160) -> Result<TransactionCall, Error> {
+        EtherDeltaEncoder::call("balanceOf", &[AbiValue::Address(token), AbiValue::Address(user)])
+    }
+
+    /// Amount of `order` (placed by `user`) that is still available to trade.
+    pub fn available_volume(order: &OrderData, user: H160) -> Result<TransactionCall, Error> {
+        EtherDeltaEncoder::call("availableVolume", &EtherDeltaEncoder::order_args(order, user)?)
+    }
+
+    /// Amount of `order` (placed by `user`) that has already been filled.
+    pub fn amount_filled(order: &OrderData, user: H160) -> Result<TransactionCall, Error> {
+        EtherDeltaEncoder::call("amountFilled", &EtherDeltaEncoder::order_args(order, user)?)
+    }
+
+    pub fn decode_balance_of(output: &str) -> Result<U256, Error> {
+        EtherDeltaEncoder::decode_uint("balanceOf", output)
+    }
+
+    pub fn decode_available_volume(output: &str) -> Result<U256, Error> {
+        EtherDeltaEncoder::decode_uint("availableVolume", output)
+    }
+
+    pub fn decode_amount_filled(output: &str) -> Result<U256, Error> {
+        EtherDeltaEncoder::decode_uint("amountFilled", output)
+    }
+
+    fn function(name: &str) -> Result<&'static Function, Error> {
+        ETHERDELTA_ABI.function(name)
+            .ok_or_else(|| ErrorKind::Abi(format!("Unknown EtherDelta function: {}", name)).into())
+    }
+
+    fn call(name: &str, args: &[AbiValue]) -> Result<TransactionCall, Error> {
+        TransactionCall::function_call(*ETHERDELTA_ADDRESS, EtherDeltaEncoder::function(name)?, args)
+    }
+
+    fn decode_uint(name: &str, output: &str) -> Result<U256, Error> {
+        EtherDeltaEncoder::function(name)?
+            .decode_output(output, DecodeMode::Strict)?
+            .first()
+            .and_then(AbiValue::to_uint)
+            .ok_or_else(|| ErrorKind::Decoder(format!("Empty {} result: {}", name, output)).into())
+    }
+
+    /// `(tokenGet, amountGet, tokenGive, amountGive, expires, nonce, user, v, r, s)`
+    fn order_args(order: &OrderData, user: H160) -> Result<Vec<AbiValue>, Error> {
+        Ok(vec![
+            AbiValue::Address(order.token_get),
+            AbiValue::Uint(order.amount_get),
+            AbiValue::Address(order.token_give),
+            AbiValue::Uint(order.amount_give),
+            AbiValue::Uint(order.expires),
+            AbiValue::Uint(order.nonce),
+            AbiValue::Address(user),
+            AbiValue::Uint(order.v),
+            AbiValue::FixedBytes(hex_to_bytes(&order.r)?),
+            AbiValue::FixedBytes(hex_to_bytes(&order.s)?)
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use objects::fixtures::proxy_traces;
+    use types::U256;
+    use super::EtherDeltaEncoder;
+    use super::super::decoder::EtherDeltaDecoder;
+    use super::super::models::EtherDeltaAction;
+
+    #[test]
+    fn reencodes_traced_calls() {
+        let traces = proxy_traces();
+
+        for trace in &traces[1..] {
+            let call = match EtherDeltaDecoder::decode_trace_action(trace).unwrap() {
+                EtherDeltaAction::AvailableVolume(order, user) => EtherDeltaEncoder::available_volume(&order, user),
+                EtherDeltaAction::BalanceOf(token, user) => EtherDeltaEncoder::balance_of(token, user),
+                _ => continue
+            };
+
+            assert_eq!(call.unwrap().data.as_ref(), Some(&trace.action.input));
+        }
+    }
+
+    #[test]
+    fn decodes_call_results() {
+        let traces = proxy_traces();
+        let output = traces[1].result.as_ref().and_then(|r| r.output.as_ref()).unwrap();
+        let volume = EtherDeltaEncoder::decode_available_volume(output).unwrap();
+
+        assert!(volume > U256::zero());
+        assert_eq!(
+            EtherDeltaEncoder::decode_balance_of(&format!("0x{:064x}", 42)).unwrap(),
+            U256::from(42)
+        );
+        assert!(EtherDeltaEncoder::decode_amount_filled("0x").is_err());
+    }
+}
@@ -1,5 +1,6 @@
 pub mod constants;
 pub mod decoder;
+pub mod encoder;
 mod models;
 
 pub use self::models::{
@@ -7,6 +8,7 @@ pub use self::models::{
     EtherDeltaProxyTransaction,
     EtherDeltaAction,
     EtherDeltaEvent,
+    EtherDeltaInteraction,
     OrderData,
     OrderLog,
     TradeLog
@@ -1,8 +1,14 @@
 use std::hash::{Hash, Hasher};
 
+use sha2::{Digest, Sha256};
 use twox_hash::XxHash;
 use contracts::{ContractFunction, NamedFunction};
+use contracts::ecdsa::{eth_message_hash, Signature};
+use contracts::token::{Price, TokenRegistry, ERC20};
+use error::{Error, ErrorKind};
+use serde::{Deserialize, Deserializer};
 use super::constants::ETHERDELTA_ADDRESS;
+use super::decoder::EtherDeltaDecoder;
 use ::objects::*;
 use ::types::*;
 
@@ -28,6 +34,29 @@ pub struct EtherDeltaTransaction {
 }
 
 impl EtherDeltaTransaction {
+    /// Builds the transaction model from a transaction sent directly to the EtherDelta
+    /// contract and its receipt.  The event is the first EtherDelta log that decodes.
+    pub fn from_chain_data(
+        tx: &Transaction,
+        receipt: &TransactionReceipt
+    ) -> Result<EtherDeltaTransaction, Error> {
+        if tx.to != Some(*ETHERDELTA_ADDRESS) {
+            return Err(ErrorKind::Decoder(
+                format!("[Tx: {:?}] Not sent to the EtherDelta contract", &tx.hash)
+            ).into());
+        }
+
+        let action = EtherDeltaDecoder::decode_action(tx)?;
+        let event = decode_receipt_events(receipt).into_iter().next();
+
+        Ok(EtherDeltaTransaction {
+            tx: tx.clone(),
+            receipt: receipt.clone(),
+            action,
+            event
+        })
+    }
+
     /// Was this transactions successful?  If not, the transaction's event will be `None`
     pub fn is_success(&self) -> bool { self.event.is_some() }
 }
@@ -52,6 +81,93 @@ pub struct EtherDeltaProxyTransaction {
     pub events: Vec<EtherDeltaEvent>
 }
 
+impl EtherDeltaProxyTransaction {
+    /// Builds the proxy transaction model from a transaction, its receipt and its traces.
+    /// Traces belonging to other transactions are ignored.  Every frame calling the EtherDelta
+    /// contract is decoded; frames that call other contracts, or EtherDelta methods that the
+    /// decoder does not know, are kept with an action of `None`.
+    pub fn from_chain_data(
+        tx: &Transaction,
+        receipt: &TransactionReceipt,
+        traces: &[ParityTrace]
+    ) -> EtherDeltaProxyTransaction {
+        let actions = traces.iter()
+            .filter(|trace| trace.transaction_hash == tx.hash)
+            .map(|trace| {
+                let action = if trace.action.to == Some(*ETHERDELTA_ADDRESS) {
+                    EtherDeltaDecoder::decode_trace_action(trace).ok()
+                } else {
+                    None
+                };
+                (trace.clone(), action)
+            })
+            .collect();
+
+        EtherDeltaProxyTransaction {
+            tx: tx.clone(),
+            receipt: receipt.clone(),
+            actions,
+            events: decode_receipt_events(receipt)
+        }
+    }
+}
+
+/// A transaction interacting with EtherDelta, classified by whether it was sent directly
+/// to the contract or through a proxy contract.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(clippy::large_enum_variant)]
+pub enum EtherDeltaInteraction {
+    Direct(EtherDeltaTransaction),
+    Proxy(EtherDeltaProxyTransaction)
+}
+
+impl EtherDeltaInteraction {
+    /// Classifies and decodes a transaction.  Fails if the transaction was neither sent to
+    /// EtherDelta nor calls it in any of its traces, or if a direct call can't be decoded.
+    pub fn from_chain_data(
+        tx: &Transaction,
+        receipt: &TransactionReceipt,
+        traces: &[ParityTrace]
+    ) -> Result<EtherDeltaInteraction, Error> {
+        if tx.to == Some(*ETHERDELTA_ADDRESS) {
+            EtherDeltaTransaction::from_chain_data(tx, receipt).map(EtherDeltaInteraction::Direct)
+        } else if traces.iter().any(|t| {
+            t.transaction_hash == tx.hash && t.action.to == Some(*ETHERDELTA_ADDRESS)
+        }) {
+            Ok(EtherDeltaInteraction::Proxy(
+                EtherDeltaProxyTransaction::from_chain_data(tx, receipt, traces)
+            ))
+        } else {
+            Err(ErrorKind::Decoder(
+                format!("[Tx: {:?}] No interaction with the EtherDelta contract", &tx.hash)
+            ).into())
+        }
+    }
+
+    pub fn is_proxy(&self) -> bool {
+        match *self {
+            EtherDeltaInteraction::Direct(_) => false,
+            EtherDeltaInteraction::Proxy(_) => true
+        }
+    }
+}
+
+impl TransactionLike for EtherDeltaInteraction {
+    fn get_tx(&self) -> &Transaction {
+        match *self {
+            EtherDeltaInteraction::Direct(ref t) => &t.tx,
+            EtherDeltaInteraction::Proxy(ref t) => &t.tx
+        }
+    }
+}
+
+fn decode_receipt_events(receipt: &TransactionReceipt) -> Vec<EtherDeltaEvent> {
+    receipt.logs.iter()
+        .filter(|log| log.address == *ETHERDELTA_ADDRESS)
+        .filter_map(|log| EtherDeltaDecoder::decode_event(log).ok())
+        .collect()
+}
+
 /// The action specified in a transaction's input.  This will either be a call to a constant
 /// method, such as a balance check, or a call with change's to the EVM storage, like a trade.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -62,6 +178,9 @@ pub enum EtherDeltaAction {
     Deposit(Amount),
     /// A deposit of a compatible token to the smart contract.
     DepositToken(Token, Amount),
+    /// An order placed on-chain by the sender, instead of signed and posted off-chain.  Rarely
+    /// seen.  The order has no signature.
+    Order(OrderData),
     /// A trade executed on-chain by an order's taker.
     Trade(OrderData, User, Amount),
     /// A withdrawal of ether from the smart contract.
@@ -91,6 +210,9 @@ impl NamedFunction for EtherDeltaAction {
             EtherDeltaAction::DepositToken(_, _) => ContractFunction::Mutable(
                 "EtherDelta.depositToken".to_string()
             ),
+            EtherDeltaAction::Order(_) => ContractFunction::Mutable(
+                "EtherDelta.order".to_string()
+            ),
             EtherDeltaAction::Trade(_, _, _) => ContractFunction::Mutable(
                 "EtherDelta.trade".to_string()
             ),
@@ -153,23 +275,42 @@ pub struct OrderData {
 }
 
 impl OrderData {
-    pub fn hash_order(&self) -> u64 {
-        let mut hasher = XxHash::default();
-        let hash_string = format!(
-            "{:?}{:?}{:?}{:?}{:?}{:?}{:?}{:?}{}{}",
-            &*ETHERDELTA_ADDRESS,
-            &self.token_get,
-            &self.amount_get,
-            &self.token_give,
-            &self.amount_give,
-            &self.expires,
-            &self.nonce,
-            &self.v,
-            &self.r,
-            &self.s
-        );
-        hash_string.hash(&mut hasher);
-        hasher.finish()
+    /// The order's `v`, `r` and `s` as a recoverable signature.
+    pub fn signature(&self) -> Result<Signature, Error> {
+        Signature::from_vrs(&self.v, &self.r, &self.s)
+    }
+
+    /// The hash the contract computes for the order: sha256 of the contract's address,
+    /// `token_get`, `amount_get`, `token_give`, `amount_give`, `expires` and `nonce`, tightly
+    /// packed.  On-chain orders are stored under it, and off-chain orders are signed over it.
+    pub fn hash_order(&self) -> H256 {
+        self.hash_order_at(&ETHERDELTA_ADDRESS)
+    }
+
+    /// The order hash for the EtherDelta deployment at `contract`.
+    pub fn hash_order_at(&self, contract: &H160) -> H256 {
+        let mut hasher = Sha256::new();
+        hasher.update(contract);
+        hasher.update(self.token_get);
+        hasher.update(H256::from(self.amount_get));
+        hasher.update(self.token_give);
+        hasher.update(H256::from(self.amount_give));
+        hasher.update(H256::from(self.expires));
+        hasher.update(H256::from(self.nonce));
+        H256::from_slice(&hasher.finalize())
+    }
+
+    /// Recovers the maker of an off-chain order from its signature of `hash_order`, prefixed
+    /// with `"\x19Ethereum Signed Message:\n32"` as the contract expects.  Orders placed
+    /// on-chain with `order` aren't signed, so this fails or returns an unrelated address.
+    pub fn maker(&self) -> Result<H160, Error> {
+        self.signature()?.recover(&eth_message_hash(&self.hash_order()))
+    }
+
+    /// Was the order signed by `maker`?  `false` for forged orders, and orders signed for
+    /// another maker.
+    pub fn is_signed_by(&self, maker: &H160) -> bool {
+        self.maker().map(|m| m == *maker).unwrap_or(false)
     }
 
     pub fn hash_trade(&self, tx_hash: &H256, maker: &H160, taker: &H160, amount: &U256) -> u64 {
@@ -251,12 +392,69 @@ pub struct TradeLog {
     /// The order's taker.  `Give` as specified by the EtherDelta smart contract.  Renamed
     /// for clarity and normalization across all Etherswap exchange models.
     pub taker: H160,
-    /// Not native to EtherDelta contract events.  This price is calculated during processing,
-    /// since the calculation relies on knowledge of a token contract's `decimals` field.
-    pub price: f64
+    /// Not native to EtherDelta contract events.  This price is set during processing by
+    /// `set_price`, since the calculation relies on knowledge of a token contract's `decimals`
+    /// field.  Records from before prices were exact hold a number here, which reads as no
+    /// price.
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "opt_price_or_legacy")]
+    pub price: Option<Price>
+}
+
+/// Reads a `Price`, or `None` for null or the `f64` that older `TradeLog`s were stored with.
+fn opt_price_or_legacy<'de, D>(deserializer: D) -> Result<Option<Price>, D::Error>
+    where D: Deserializer<'de>
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StoredPrice {
+        Exact(Price),
+        Legacy(#[allow(dead_code)] f64)
+    }
+
+    Ok(match Option::<StoredPrice>::deserialize(deserializer)? {
+        Some(StoredPrice::Exact(price)) => Some(price),
+        Some(StoredPrice::Legacy(_)) | None => None
+    })
 }
 
 impl TradeLog {
+    /// The exact price of the trade, given the decimals of `token_get` and `token_give` (18 for
+    /// ether).  When the maker gives ether, this is the ether paid per `token_get`; otherwise
+    /// it's the amount of `token_get` paid per `token_give`, which is ether per token when the
+    /// maker sells tokens for ether.
+    pub fn calculate_price(&self, get_decimals: u8, give_decimals: u8) -> Result<Price, Error> {
+        if self.token_give.is_zero() {
+            Price::from_raw(self.amount_give, give_decimals, self.amount_get, get_decimals)
+        } else {
+            Price::from_raw(self.amount_get, get_decimals, self.amount_give, give_decimals)
+        }
+    }
+
+    pub fn set_price(&mut self, get_decimals: u8, give_decimals: u8) -> Result<(), Error> {
+        self.price = Some(self.calculate_price(get_decimals, give_decimals)?);
+        Ok(())
+    }
+
+    /// Sets the price with the decimals of the traded tokens' records.
+    pub fn set_price_from_tokens(&mut self, get: &ERC20, give: &ERC20) -> Result<(), Error> {
+        self.set_price(get.decimals, give.decimals)
+    }
+
+    /// Sets the price with the decimals of the traded tokens in `registry`.  Fails if a token
+    /// isn't registered for the chain.
+    pub fn set_price_from_registry(&mut self, registry: &TokenRegistry, chain_id: u64) -> Result<(), Error> {
+        let decimals = |token: &H160| -> Result<u8, Error> {
+            if token.is_zero() {
+                return Ok(18);
+            }
+            registry.get(chain_id, token)
+                .map(|t| t.decimals)
+                .ok_or_else(|| ErrorKind::Amount(format!("Unknown token on chain {}: {:?}", chain_id, token)).into())
+        };
+        let (get_decimals, give_decimals) = (decimals(&self.token_get)?, decimals(&self.token_give)?);
+        self.set_price(get_decimals, give_decimals)
+    }
+
     pub fn hash_trade(&self, tx_hash: &H256) -> u64 {
         let mut hasher = XxHash::default();
         let hash_string = format!(
@@ -288,4 +486,203 @@ impl TradeLog {
         hash_string.hash(&mut hasher);
         hasher.finish()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use serde_json;
+    use objects::fixtures::proxy_traces;
+    use objects::{Log, Transaction, TransactionReceipt};
+    use types::{H160, H256, U256};
+    use super::*;
+    use super::super::constants::DEPOSIT_ID;
+
+    fn withdraw_tx() -> Transaction {
+        Transaction {
+            hash: H256::from_str(
+                "381938d35762311bf38f0190a2a8f77717c10b451c60db84335589b0b3222c52"
+            ).unwrap(),
+            from: H160::from_str("461680c41786ec889ac9de7f5e89a2f4e0fcb156").unwrap(),
+            to: Some(*ETHERDELTA_ADDRESS),
+            input: "0x2e1a7d4d00000000000000000000000000000000000000000000000000071afd498d0000"
+                .to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn withdraw_log() -> Log {
+        Log {
+            address: *ETHERDELTA_ADDRESS,
+            topics: vec![*::contracts::etherdelta::constants::ETHERDELTA_WITHDRAW_TOPIC],
+            data: format!(
+                "0x{:064x}{:0>64}{:064x}{:064x}",
+                0, "461680c41786ec889ac9de7f5e89a2f4e0fcb156", 0x71afd498d0000_u64, 0
+            ),
+            ..Default::default()
+        }
+    }
+
+    fn proxy_tx() -> Transaction {
+        Transaction {
+            hash: H256::from_str(
+                "91169ed3510c36785e9d16817e92751100ff7e51654da79f3ce47ac35e0df6dc"
+            ).unwrap(),
+            from: H160::from_str("000029c8bf6f0790f2bbc8afee6726e2b554a58e").unwrap(),
+            to: Some(H160::from_str("f43b43869dd95e53923b30a79902915c1faa47d3").unwrap()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn classifies_direct_transactions() {
+        let receipt = TransactionReceipt { logs: vec![withdraw_log()], ..Default::default() };
+        let interaction = EtherDeltaInteraction::from_chain_data(&withdraw_tx(), &receipt, &[])
+            .unwrap();
+
+        match interaction {
+            EtherDeltaInteraction::Direct(ref t) => {
+                match t.action {
+                    EtherDeltaAction::Withdraw(amount) => {
+                        assert_eq!(amount, U256::from(0x71afd498d0000_u64))
+                    },
+                    _ => panic!("Expected a withdraw action")
+                }
+                assert!(t.is_success());
+            },
+            _ => panic!("Expected a direct transaction")
+        }
+    }
+
+    #[test]
+    fn classifies_proxy_transactions() {
+        let receipt = TransactionReceipt::default();
+        let interaction = EtherDeltaInteraction::from_chain_data(
+            &proxy_tx(), &receipt, &proxy_traces()
+        ).unwrap();
+        assert!(interaction.is_proxy());
+
+        match interaction {
+            EtherDeltaInteraction::Proxy(ref t) => {
+                assert_eq!(t.actions.len(), 8);
+                assert!(t.actions[0].1.is_none());
+
+                let trades = t.actions.iter()
+                    .filter(|&(_, a)| matches!(*a, Some(EtherDeltaAction::Trade(_, _, _))))
+                    .count();
+                assert_eq!(trades, 2);
+                assert!(t.actions[1..].iter().all(|(_, a)| a.is_some()));
+            },
+            _ => panic!("Expected a proxy transaction")
+        }
+    }
+
+    #[test]
+    fn recovers_makers_of_signed_orders() {
+        let trades = proxy_traces().iter()
+            .filter_map(|trace| match EtherDeltaDecoder::decode_trace_action(trace) {
+                Ok(EtherDeltaAction::Trade(order, maker, _)) => Some((order, maker)),
+                _ => None
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(trades.len(), 2);
+
+        for (order, maker) in trades {
+            assert_eq!(order.maker().unwrap(), maker);
+            assert!(order.is_signed_by(&maker));
+            assert_ne!(order.hash_order(), order.hash_order_at(&H160::zero()));
+
+            let forged = OrderData { amount_give: order.amount_give + U256::one(), ..order.clone() };
+            assert!(!forged.is_signed_by(&maker));
+            assert!(!OrderData { v: U256::from(29), ..order }.is_signed_by(&maker));
+        }
+    }
+
+    fn trade(token_get: &str, amount_get: &str, token_give: &str, amount_give: &str) -> TradeLog {
+        TradeLog {
+            token_get: H160::from_str(token_get).unwrap(),
+            amount_get: U256::from_dec_str(amount_get).unwrap(),
+            token_give: H160::from_str(token_give).unwrap(),
+            amount_give: U256::from_dec_str(amount_give).unwrap(),
+            maker: H160::from(1),
+            taker: H160::from(2),
+            price: None
+        }
+    }
+
+    #[test]
+    fn prices_trades_in_ether() {
+        let zrx = "e41d2489571d322189246dafa5ebde1f4699f498";
+        let ether = "0000000000000000000000000000000000000000";
+
+        // The maker buys 2500 ZRX for 1 ether.
+        let mut buy = trade(zrx, "2500000000000000000000", ether, "1000000000000000000");
+        buy.set_price(18, 18).unwrap();
+        assert_eq!(buy.price.as_ref().unwrap().to_string(), "0.0004");
+        assert!(serde_json::to_string(&buy).unwrap().contains("\"price\":\"1/2500\""));
+
+        // The maker sells 150 of a 6 decimal token for 0.3 ether.
+        let sell = trade(ether, "300000000000000000", zrx, "150000000");
+        assert_eq!(sell.calculate_price(18, 6).unwrap().to_string(), "0.002");
+        assert!(trade(zrx, "0", ether, "1").calculate_price(18, 18).is_err());
+
+        let mut registry = TokenRegistry::new();
+        registry.load_token_list(include_str!("../../../test_data/token_list.json")).unwrap();
+        buy.price = None;
+        buy.set_price_from_registry(&registry, 1).unwrap();
+        assert_eq!(buy.price.as_ref().unwrap().to_string(), "0.0004");
+        assert!(buy.set_price_from_registry(&registry, 42).is_err());
+    }
+
+    #[test]
+    fn reads_trades_stored_with_float_prices() {
+        let legacy = r#"{"token_get":"0xe41d2489571d322189246dafa5ebde1f4699f498","amount_get":"0x878678326eac900000","token_give":"0x0000000000000000000000000000000000000000","amount_give":"0xde0b6b3a7640000","maker":"0x0000000000000000000000000000000000000001","taker":"0x0000000000000000000000000000000000000002","price":0.0}"#;
+        let mut trade = serde_json::from_str::<TradeLog>(legacy).unwrap();
+        assert!(trade.price.is_none());
+        assert_eq!(trade.amount_get, U256::from_dec_str("2500000000000000000000").unwrap());
+        assert!(serde_json::from_str::<TradeLog>(&legacy.replace("0.0", "0.0004")).unwrap().price.is_none());
+        assert!(serde_json::from_str::<TradeLog>(&legacy.replace("0.0", "null")).unwrap().price.is_none());
+
+        let unpriced = serde_json::to_string(&trade).unwrap();
+        assert!(!unpriced.contains("price"));
+        assert!(serde_json::from_str::<TradeLog>(&unpriced).unwrap().price.is_none());
+
+        trade.set_price(18, 18).unwrap();
+        let priced = serde_json::from_str::<TradeLog>(&serde_json::to_string(&trade).unwrap()).unwrap();
+        assert_eq!(priced.price, trade.price);
+        assert!(serde_json::from_str::<TradeLog>(&legacy.replace("0.0", "\"1/0\"")).is_err());
+    }
+
+    #[test]
+    fn skips_malformed_proxy_frames() {
+        let mut traces = proxy_traces();
+        let input = traces[1].action.input.clone();
+        traces[1].action.input = input[..input.len() - 64].to_string();
+        traces[2].action.input = format!("{}zz{}", &input[..10], &input[12..]);
+        traces[3].action.input = DEPOSIT_ID.to_string();
+        traces[3].action.value = None;
+
+        let interaction = EtherDeltaInteraction::from_chain_data(
+            &proxy_tx(), &TransactionReceipt::default(), &traces
+        ).unwrap();
+
+        match interaction {
+            EtherDeltaInteraction::Proxy(ref t) => {
+                assert!(t.actions[1].1.is_none());
+                assert!(t.actions[2].1.is_none());
+                match t.actions[3].1 {
+                    Some(EtherDeltaAction::Deposit(value)) => assert!(value.is_zero()),
+                    ref other => panic!("Expected a deposit, got {:?}", other)
+                }
+            },
+            _ => panic!("Expected a proxy transaction")
+        }
+    }
+
+    #[test]
+    fn rejects_unrelated_transactions() {
+        let receipt = TransactionReceipt::default();
+        assert!(EtherDeltaInteraction::from_chain_data(&proxy_tx(), &receipt, &[]).is_err());
+    }
+}
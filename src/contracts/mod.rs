@@ -1,12 +1,15 @@
+pub mod abi;
+pub mod ecdsa;
+pub mod eip712;
 pub mod etherdelta;
+pub mod token;
 
+use std::fmt;
 use std::str;
-use std::string::ToString;
 
 use fixed_hash::clean_0x;
 
 use error::{Error, ErrorKind};
-use types::{H160, U256};
 
 pub trait NamedFunction {
     fn get_function(&self) -> ContractFunction;
@@ -17,11 +20,11 @@ pub enum ContractFunction {
     Mutable(String)
 }
 
-impl ToString for ContractFunction {
-    fn to_string(&self) -> String {
+impl fmt::Display for ContractFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
-            ContractFunction::Immutable(ref s) => s.to_string(),
-            ContractFunction::Mutable(ref s) => s.to_string()
+            ContractFunction::Immutable(ref s) => write!(f, "{}", s),
+            ContractFunction::Mutable(ref s) => write!(f, "{}", s)
         }
     }
 }
@@ -33,12 +36,12 @@ pub fn clean_method_id(data: &str, num_params: usize) -> &str {
     if data.len() == expected_length + 8 {
         &data[8..]
     } else {
-        &data
+        data
     }
 }
 
 pub fn normalize_data(data: &str, num_params: usize) -> Result<Vec<String>, Error> {
-    let data = clean_method_id(&data, num_params);
+    let data = clean_method_id(data, num_params);
     let expected_length = 64 * num_params;
 
     if data.len() != expected_length {
@@ -60,7 +63,7 @@ mod tests {
     #[test]
     fn cleans_and_normalizes_prefixed_data() {
         let data = include_str!("../../test_data/input_data_0x.txt");
-        let cleaned_and_normalized_data = normalize_data(&data, 11).unwrap();
+        let cleaned_and_normalized_data = normalize_data(data, 11).unwrap();
 
         assert_eq!(cleaned_and_normalized_data.len(), 11);
         assert!(cleaned_and_normalized_data.iter().all(|chunk| chunk.len() == 64));
@@ -69,7 +72,7 @@ mod tests {
     #[test]
     fn cleans_and_normalizes_data_without_prefix() {
         let data = include_str!("../../test_data/input_data_no_prefix.txt");
-        let cleaned_and_normalized_data = normalize_data(&data, 11).unwrap();
+        let cleaned_and_normalized_data = normalize_data(data, 11).unwrap();
 
         assert_eq!(cleaned_and_normalized_data.len(), 11);
         assert!(cleaned_and_normalized_data.iter().all(|chunk| chunk.len() == 64));
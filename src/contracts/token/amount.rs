@@ -0,0 +1,211 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use error::{Error, ErrorKind};
+use types::U256;
+
+use super::ERC20;
+
+/// An amount of a token: the raw integer the contract stores, with the decimals and symbol
+/// needed to display it.  Two amounts are of the same token if their decimals and symbols
+/// match; amounts of different tokens can't be added, subtracted or ordered.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TokenAmount {
+    /// Amount in the token's smallest unit.
+    pub raw: U256,
+    pub decimals: u8,
+    pub symbol: String
+}
+
+impl TokenAmount {
+    pub fn new(raw: U256, decimals: u8, symbol: &str) -> TokenAmount {
+        TokenAmount { raw, decimals, symbol: symbol.to_string() }
+    }
+
+    pub fn of(token: &ERC20, raw: U256) -> TokenAmount {
+        TokenAmount::new(raw, token.decimals, &token.symbol)
+    }
+
+    /// An amount of ether, given in wei.
+    pub fn ether(wei: U256) -> TokenAmount {
+        TokenAmount::new(wei, 18, "ETH")
+    }
+
+    /// Parses amounts such as `"12.5 ZRX"` or `"12.5"` of `token`.  The symbol, if given, must
+    /// be the token's.
+    pub fn parse(s: &str, token: &ERC20) -> Result<TokenAmount, Error> {
+        TokenAmount::parse_units(s, token.decimals, &token.symbol)
+    }
+
+    /// Parses amounts of a token with the given decimals and symbol.  Amounts with more
+    /// fractional digits than the token has decimals are rejected rather than rounded.
+    pub fn parse_units(s: &str, decimals: u8, symbol: &str) -> Result<TokenAmount, Error> {
+        let mut parts = s.split_whitespace();
+        let number = parts.next().ok_or_else(|| amount_error(format!("Empty amount: {:?}", s)))?;
+
+        match (parts.next(), parts.next()) {
+            (None, _) => {},
+            (Some(given), None) if given == symbol => {},
+            (Some(given), None) => {
+                return Err(amount_error(format!("Expected an amount of {}, got {}", symbol, given)));
+            },
+            _ => return Err(amount_error(format!("Invalid amount: {:?}", s)))
+        }
+
+        let (whole, fraction) = match number.find('.') {
+            Some(i) => (&number[..i], &number[i + 1..]),
+            None => (number, "")
+        };
+
+        let is_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+        if (whole.is_empty() && fraction.is_empty()) || !is_digits(whole) || !is_digits(fraction) {
+            return Err(amount_error(format!("Invalid number: {:?}", number)));
+        }
+        if fraction.len() > decimals as usize {
+            return Err(amount_error(format!("{} has more than {} decimals", number, decimals)));
+        }
+
+        let digits = format!("{}{:0<width$}", whole, fraction, width = decimals as usize);
+        let raw = U256::from_dec_str(if digits.is_empty() { "0" } else { &digits })
+            .map_err(|_| amount_error(format!("Amount out of range: {}", number)))?;
+
+        Ok(TokenAmount::new(raw, decimals, symbol))
+    }
+
+    pub fn is_same_token(&self, other: &TokenAmount) -> bool {
+        self.decimals == other.decimals && self.symbol == other.symbol
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.raw.is_zero()
+    }
+
+    /// `None` on overflow, or if `other` is of a different token.
+    pub fn checked_add(&self, other: &TokenAmount) -> Option<TokenAmount> {
+        if !self.is_same_token(other) {
+            return None;
+        }
+        self.raw.checked_add(other.raw).map(|raw| TokenAmount { raw, ..self.clone() })
+    }
+
+    /// `None` on underflow, or if `other` is of a different token.
+    pub fn checked_sub(&self, other: &TokenAmount) -> Option<TokenAmount> {
+        if !self.is_same_token(other) {
+            return None;
+        }
+        self.raw.checked_sub(other.raw).map(|raw| TokenAmount { raw, ..self.clone() })
+    }
+
+    pub fn checked_mul(&self, factor: U256) -> Option<TokenAmount> {
+        self.raw.checked_mul(factor).map(|raw| TokenAmount { raw, ..self.clone() })
+    }
+
+    pub fn checked_div(&self, divisor: U256) -> Option<TokenAmount> {
+        self.raw.checked_div(divisor).map(|raw| TokenAmount { raw, ..self.clone() })
+    }
+
+    /// The exact amount in whole tokens, without trailing zeros or the symbol: `"12.5"`.
+    pub fn to_decimal_string(&self) -> String {
+        let digits = format!("{}", self.raw);
+        let decimals = self.decimals as usize;
+        if decimals == 0 {
+            return digits;
+        }
+
+        let digits = format!("{:0>width$}", digits, width = decimals + 1);
+        let (whole, fraction) = digits.split_at(digits.len() - decimals);
+        let fraction = fraction.trim_end_matches('0');
+
+        if fraction.is_empty() {
+            whole.to_string()
+        } else {
+            format!("{}.{}", whole, fraction)
+        }
+    }
+}
+
+impl PartialOrd for TokenAmount {
+    /// Only amounts of the same token are comparable.
+    fn partial_cmp(&self, other: &TokenAmount) -> Option<Ordering> {
+        if self.is_same_token(other) {
+            Some(self.raw.cmp(&other.raw))
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for TokenAmount {
+    /// `"12.5 ZRX"`, or just `"12.5"` for tokens without a symbol.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.symbol.is_empty() {
+            write!(f, "{}", self.to_decimal_string())
+        } else {
+            write!(f, "{} {}", self.to_decimal_string(), self.symbol)
+        }
+    }
+}
+
+fn amount_error(message: String) -> Error {
+    ErrorKind::Amount(message).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use contracts::token::ERC20;
+    use types::U256;
+    use super::TokenAmount;
+
+    fn zrx() -> ERC20 {
+        ERC20 {
+            name: "0x Protocol Token".to_string(),
+            symbol: "ZRX".to_string(),
+            decimals: 18,
+            total_supply: U256::from_dec_str("1000000000000000000000000000").unwrap(),
+            icon_url: None
+        }
+    }
+
+    #[test]
+    fn displays_exact_amounts() {
+        let amount = TokenAmount::of(&zrx(), U256::from_dec_str("12500000000000000000").unwrap());
+        assert_eq!(amount.to_string(), "12.5 ZRX");
+        assert_eq!(TokenAmount::new(U256::from(1), 18, "").to_string(), "0.000000000000000001");
+        assert_eq!(TokenAmount::new(U256::from(1200), 2, "X").to_string(), "12 X");
+        assert_eq!(TokenAmount::new(U256::from(7), 0, "X").to_string(), "7 X");
+        assert_eq!(TokenAmount::ether(U256::zero()).to_string(), "0 ETH");
+    }
+
+    #[test]
+    fn parses_amounts() {
+        let amount = TokenAmount::parse("12.5 ZRX", &zrx()).unwrap();
+        assert_eq!(amount.raw, U256::from_dec_str("12500000000000000000").unwrap());
+        assert_eq!(TokenAmount::parse(".5", &zrx()).unwrap().raw, U256::from_str("6f05b59d3b20000").unwrap());
+        assert_eq!(TokenAmount::parse_units("3", 0, "X").unwrap().raw, U256::from(3));
+
+        assert!(TokenAmount::parse("12.5 MKR", &zrx()).is_err());
+        assert!(TokenAmount::parse("-1 ZRX", &zrx()).is_err());
+        assert!(TokenAmount::parse(". ZRX", &zrx()).is_err());
+        assert!(TokenAmount::parse_units("1.25", 1, "X").is_err());
+        assert!(TokenAmount::parse_units(&format!("1{:0>80}", 0), 0, "X").is_err());
+    }
+
+    #[test]
+    fn checks_arithmetic_and_tokens() {
+        let a = TokenAmount::parse("1.5 ZRX", &zrx()).unwrap();
+        let b = TokenAmount::parse("2 ZRX", &zrx()).unwrap();
+        let eth = TokenAmount::ether(U256::from(1));
+
+        assert_eq!(a.checked_add(&b).unwrap().to_string(), "3.5 ZRX");
+        assert_eq!(b.checked_sub(&a).unwrap().to_string(), "0.5 ZRX");
+        assert!(a.checked_sub(&b).is_none());
+        assert!(a.checked_add(&eth).is_none());
+        assert!(TokenAmount::new(U256::max_value(), 0, "X").checked_mul(U256::from(2)).is_none());
+        assert_eq!(b.checked_div(U256::from(4)).unwrap().to_string(), "0.5 ZRX");
+
+        assert!(a < b);
+        assert!(a.partial_cmp(&eth).is_none());
+    }
+}
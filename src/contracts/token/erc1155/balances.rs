@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+
+use error::{Error, ErrorKind};
+use objects::Log;
+use types::{H160, U256};
+
+use super::decoder::ERC1155Decoder;
+use super::models::{ERC1155Event, ERC1155TransferSingle};
+
+/// Balances of every `(owner, id)` pair seen in a replay of transfer events, per token
+/// contract.  Transfers must be applied in the order they were emitted, from the token's
+/// creation: a transfer of more than the sender holds is rejected.
+#[derive(Debug, Clone, Default)]
+pub struct BalanceTracker {
+    balances: HashMap<(H160, H160, U256), U256>
+}
+
+impl BalanceTracker {
+    pub fn new() -> BalanceTracker {
+        BalanceTracker::default()
+    }
+
+    /// Moves the value from the sender to the recipient.  Mints (from `0x0`) only credit the
+    /// recipient and burns (to `0x0`) only debit the sender.  Both balances are checked before
+    /// either is written, so a rejected transfer leaves the tracker unchanged.
+    pub fn apply(&mut self, transfer: &ERC1155TransferSingle) -> Result<(), Error> {
+        let from = (transfer.token, transfer.from, transfer.id);
+        let to = (transfer.token, transfer.to, transfer.id);
+
+        let debited = if transfer.from.is_zero() {
+            None
+        } else {
+            let balance = self.balances.get(&from).cloned().unwrap_or_default();
+            Some(balance.checked_sub(transfer.value).ok_or_else(|| {
+                Error::from(ErrorKind::Amount(format!(
+                    "{:?} holds {} of id {}, can't send {}", transfer.from, balance, transfer.id, transfer.value
+                )))
+            })?)
+        };
+
+        let credited = if transfer.to.is_zero() {
+            None
+        } else {
+            let balance = match debited {
+                Some(debited) if from == to => debited,
+                _ => self.balances.get(&to).cloned().unwrap_or_default()
+            };
+            Some(balance.checked_add(transfer.value).ok_or_else(|| {
+                Error::from(ErrorKind::Amount(format!("Balance of {:?} overflows", transfer.to)))
+            })?)
+        };
+
+        if let Some(balance) = debited {
+            self.set(from, balance);
+        }
+        if let Some(balance) = credited {
+            self.set(to, balance);
+        }
+
+        Ok(())
+    }
+
+    /// Applies the transfers of a batch in order.  If one fails, none are applied.
+    pub fn apply_all(&mut self, transfers: &[ERC1155TransferSingle]) -> Result<(), Error> {
+        for (i, transfer) in transfers.iter().enumerate() {
+            if let Err(e) = self.apply(transfer) {
+                for applied in transfers[..i].iter().rev() {
+                    self.apply(&ERC1155TransferSingle { from: applied.to, to: applied.from, ..applied.clone() })
+                        .expect("reverting an applied transfer");
+                }
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Decodes and applies a `TransferSingle` or `TransferBatch` log.  Other ERC1155 events
+    /// are ignored.
+    pub fn apply_log(&mut self, log: &Log) -> Result<(), Error> {
+        match ERC1155Decoder::decode_event(log)? {
+            ERC1155Event::TransferSingle(transfer) => self.apply(&transfer),
+            ERC1155Event::TransferBatch(batch) => self.apply_all(&batch.transfers()),
+            _ => Ok(())
+        }
+    }
+
+    pub fn balance_of(&self, token: &H160, owner: &H160, id: &U256) -> U256 {
+        self.balances.get(&(*token, *owner, *id)).cloned().unwrap_or_default()
+    }
+
+    /// The non-zero balances of `owner` in a token contract, by ascending id.
+    pub fn balances_of(&self, token: &H160, owner: &H160) -> Vec<(U256, U256)> {
+        let mut balances = self.balances.iter()
+            .filter(|&(&(t, o, _), _)| t == *token && o == *owner)
+            .map(|(&(_, _, id), balance)| (id, *balance))
+            .collect::<Vec<_>>();
+        balances.sort();
+        balances
+    }
+
+    fn set(&mut self, key: (H160, H160, U256), balance: U256) {
+        if balance.is_zero() {
+            self.balances.remove(&key);
+        } else {
+            self.balances.insert(key, balance);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use types::{H160, U256};
+    use super::BalanceTracker;
+    use super::super::models::{ERC1155TransferBatch, ERC1155TransferSingle};
+
+    fn transfer(from: u64, to: u64, id: u64, value: u64) -> ERC1155TransferSingle {
+        ERC1155TransferSingle {
+            token: H160::from(1),
+            operator: H160::from(from),
+            from: H160::from(from),
+            to: H160::from(to),
+            id: U256::from(id),
+            value: U256::from(value)
+        }
+    }
+
+    #[test]
+    fn tracks_balances() {
+        let mut tracker = BalanceTracker::new();
+        let token = H160::from(1);
+
+        tracker.apply(&transfer(0, 10, 1, 100)).unwrap();
+        tracker.apply(&transfer(10, 20, 1, 40)).unwrap();
+        tracker.apply(&transfer(20, 0, 1, 15)).unwrap();
+
+        assert_eq!(tracker.balance_of(&token, &H160::from(10), &U256::from(1)), U256::from(60));
+        assert_eq!(tracker.balance_of(&token, &H160::from(20), &U256::from(1)), U256::from(25));
+        assert_eq!(tracker.balance_of(&token, &H160::zero(), &U256::from(1)), U256::zero());
+        assert!(tracker.apply(&transfer(20, 10, 1, 26)).is_err());
+    }
+
+    #[test]
+    fn rejects_overflowing_credits_without_debiting() {
+        let mut tracker = BalanceTracker::new();
+        let token = H160::from(1);
+        tracker.apply(&ERC1155TransferSingle { value: U256::max_value(), ..transfer(0, 20, 1, 0) }).unwrap();
+        tracker.apply(&transfer(0, 10, 1, 1)).unwrap();
+
+        assert!(tracker.apply(&transfer(10, 20, 1, 1)).is_err());
+        assert_eq!(tracker.balance_of(&token, &H160::from(10), &U256::from(1)), U256::from(1));
+        assert_eq!(tracker.balance_of(&token, &H160::from(20), &U256::from(1)), U256::max_value());
+
+        tracker.apply(&transfer(10, 10, 1, 1)).unwrap();
+        assert_eq!(tracker.balance_of(&token, &H160::from(10), &U256::from(1)), U256::from(1));
+    }
+
+    #[test]
+    fn applies_batches_atomically() {
+        let mut tracker = BalanceTracker::new();
+        let token = H160::from(1);
+        tracker.apply(&transfer(0, 10, 1, 5)).unwrap();
+
+        let batch = ERC1155TransferBatch {
+            token,
+            operator: H160::from(10),
+            from: H160::from(10),
+            to: H160::from(20),
+            ids: vec![U256::from(1), U256::from(2)],
+            values: vec![U256::from(5), U256::from(1)]
+        };
+        assert!(tracker.apply_all(&batch.transfers()).is_err());
+        assert_eq!(tracker.balances_of(&token, &H160::from(10)), vec![(U256::from(1), U256::from(5))]);
+        assert!(tracker.balances_of(&token, &H160::from(20)).is_empty());
+
+        tracker.apply(&transfer(0, 10, 2, 3)).unwrap();
+        tracker.apply_all(&batch.transfers()).unwrap();
+        assert_eq!(
+            tracker.balances_of(&token, &H160::from(20)),
+            vec![(U256::from(1), U256::from(5)), (U256::from(2), U256::from(1))]
+        );
+    }
+}
@@ -0,0 +1,85 @@
+use contracts::abi::Contract;
+use types::H256;
+
+lazy_static! {
+    /// The events and functions of the ERC1155 standard.
+    pub static ref ERC1155_ABI: Contract = Contract::from_signatures(vec![
+        "event TransferSingle(address indexed operator, address indexed from, address indexed to, uint256 id, uint256 value)",
+        "event TransferBatch(address indexed operator, address indexed from, address indexed to, uint256[] ids, uint256[] values)",
+        "event ApprovalForAll(address indexed account, address indexed operator, bool approved)",
+        "event URI(string value, uint256 indexed id)",
+        "function safeTransferFrom(address from, address to, uint256 id, uint256 amount, bytes data)",
+        "function safeBatchTransferFrom(address from, address to, uint256[] ids, uint256[] amounts, bytes data)",
+        "function setApprovalForAll(address operator, bool approved)",
+        "function balanceOf(address account, uint256 id) view returns (uint256)",
+        "function balanceOfBatch(address[] accounts, uint256[] ids) view returns (uint256[])",
+        "function isApprovedForAll(address account, address operator) view returns (bool)",
+        "function uri(uint256 id) view returns (string)"
+    ]).expect("Invalid ERC1155 ABI");
+
+    pub static ref ERC1155_TRANSFER_SINGLE_TOPIC: H256 = event_topic("TransferSingle");
+    pub static ref ERC1155_TRANSFER_BATCH_TOPIC: H256 = event_topic("TransferBatch");
+    /// Shared with ERC721's `ApprovalForAll`, which has the same layout.
+    pub static ref ERC1155_APPROVAL_FOR_ALL_TOPIC: H256 = event_topic("ApprovalForAll");
+    pub static ref ERC1155_URI_TOPIC: H256 = event_topic("URI");
+
+    // Methods
+    pub static ref SAFE_TRANSFER_FROM_ID: String = method_id("safeTransferFrom");
+    pub static ref SAFE_BATCH_TRANSFER_FROM_ID: String = method_id("safeBatchTransferFrom");
+    pub static ref SET_APPROVAL_FOR_ALL_ID: String = method_id("setApprovalForAll");
+
+    // Static methods
+    pub static ref BALANCE_OF_ID: String = method_id("balanceOf");
+    pub static ref BALANCE_OF_BATCH_ID: String = method_id("balanceOfBatch");
+    pub static ref IS_APPROVED_FOR_ALL_ID: String = method_id("isApprovedForAll");
+    pub static ref URI_ID: String = method_id("uri");
+}
+
+fn method_id(name: &str) -> String {
+    ERC1155_ABI.function(name)
+        .map(|f| f.method_id())
+        .unwrap_or_else(|| panic!("ERC1155 ABI is missing function {}", name))
+}
+
+fn event_topic(name: &str) -> H256 {
+    ERC1155_ABI.event(name)
+        .map(|e| e.topic())
+        .unwrap_or_else(|| panic!("ERC1155 ABI is missing event {}", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use contracts::token::erc721::constants::ERC721_APPROVAL_FOR_ALL_TOPIC;
+    use types::H256;
+    use super::*;
+
+    #[test]
+    fn topics_match_standard() {
+        assert_eq!(
+            *ERC1155_TRANSFER_SINGLE_TOPIC,
+            H256::from_str("c3d58168c5ae7397731d063d5bbf3d657854427343f4c083240f7aacaa2d0f62").unwrap()
+        );
+        assert_eq!(
+            *ERC1155_TRANSFER_BATCH_TOPIC,
+            H256::from_str("4a39dc06d4c0dbc64b70af90fd698a233a518aa5d07e595d983b8c0526c8f7fb").unwrap()
+        );
+        assert_eq!(
+            *ERC1155_URI_TOPIC,
+            H256::from_str("6bb7ff708619ba0610cba295a58592e0451dee2622938c8755667688daf3529b").unwrap()
+        );
+        assert_eq!(*ERC1155_APPROVAL_FOR_ALL_TOPIC, *ERC721_APPROVAL_FOR_ALL_TOPIC);
+    }
+
+    #[test]
+    fn method_ids_match_standard() {
+        assert_eq!(*SAFE_TRANSFER_FROM_ID, "0xf242432a");
+        assert_eq!(*SAFE_BATCH_TRANSFER_FROM_ID, "0x2eb2c2d6");
+        assert_eq!(*SET_APPROVAL_FOR_ALL_ID, "0xa22cb465");
+        assert_eq!(*BALANCE_OF_ID, "0x00fdd58e");
+        assert_eq!(*BALANCE_OF_BATCH_ID, "0x4e1273f4");
+        assert_eq!(*IS_APPROVED_FOR_ALL_ID, "0xe985e9c5");
+        assert_eq!(*URI_ID, "0x0e89341c");
+    }
+}
@@ -0,0 +1,276 @@
+use std::vec;
+
+use contracts::abi::{take_value, AbiValue, DecodeMode, DecodedLog, FromAbiValue};
+use error::{Error, ErrorKind};
+use objects::{Log, ParityTrace, Transaction};
+use types::U256;
+
+use super::constants::*;
+use super::models::*;
+
+/// Decoder for the calls to and events of any ERC1155 token contract.
+pub struct ERC1155Decoder;
+
+impl ERC1155Decoder {
+    pub fn decode_action(tx: &Transaction) -> Result<ERC1155Action, Error> {
+        ERC1155Decoder::decode_input(&tx.input)
+    }
+
+    pub fn decode_trace_action(trace: &ParityTrace) -> Result<ERC1155Action, Error> {
+        ERC1155Decoder::decode_input(&trace.action.input)
+    }
+
+    pub fn decode_input(input: &str) -> Result<ERC1155Action, Error> {
+        let method_id = match input.get(..10) {
+            Some(id) => id.to_lowercase(),
+            None => return Err(decode_error(format!("Input is too short for an ERC1155 call: {}", input)))
+        };
+
+        match method_id.as_str() {
+            id if id == *SAFE_TRANSFER_FROM_ID => {
+                let mut args = call_args("safeTransferFrom", input)?;
+                Ok(ERC1155Action::SafeTransferFrom(
+                    take_value(&mut args)?,
+                    take_value(&mut args)?,
+                    take_value(&mut args)?,
+                    take_value(&mut args)?,
+                    take_value(&mut args)?
+                ))
+            },
+            id if id == *SAFE_BATCH_TRANSFER_FROM_ID => {
+                let mut args = call_args("safeBatchTransferFrom", input)?;
+                let (from, to) = (take_value(&mut args)?, take_value(&mut args)?);
+                let (ids, amounts) = (take_array(&mut args)?, take_array(&mut args)?);
+                check_lengths(&ids, &amounts)?;
+                Ok(ERC1155Action::SafeBatchTransferFrom(from, to, ids, amounts, take_value(&mut args)?))
+            },
+            id if id == *SET_APPROVAL_FOR_ALL_ID => {
+                let mut args = call_args("setApprovalForAll", input)?;
+                Ok(ERC1155Action::SetApprovalForAll(take_value(&mut args)?, take_value(&mut args)?))
+            },
+            id if id == *BALANCE_OF_ID => {
+                let mut args = call_args("balanceOf", input)?;
+                Ok(ERC1155Action::BalanceOf(take_value(&mut args)?, take_value(&mut args)?))
+            },
+            id if id == *BALANCE_OF_BATCH_ID => {
+                let mut args = call_args("balanceOfBatch", input)?;
+                let (accounts, ids) = (take_array(&mut args)?, take_array(&mut args)?);
+                check_lengths(&accounts, &ids)?;
+                Ok(ERC1155Action::BalanceOfBatch(accounts, ids))
+            },
+            id if id == *IS_APPROVED_FOR_ALL_ID => {
+                let mut args = call_args("isApprovedForAll", input)?;
+                Ok(ERC1155Action::IsApprovedForAll(take_value(&mut args)?, take_value(&mut args)?))
+            },
+            id if id == *URI_ID => {
+                let mut args = call_args("uri", input)?;
+                Ok(ERC1155Action::Uri(take_value(&mut args)?))
+            },
+            _ => Err(decode_error(format!("Not an ERC1155 method: {}", method_id)))
+        }
+    }
+
+    pub fn decode_event(log: &Log) -> Result<ERC1155Event, Error> {
+        match log.topics.first() {
+            Some(t) if *t == *ERC1155_TRANSFER_SINGLE_TOPIC => {
+                ERC1155Decoder::decode_transfer_single(log).map(ERC1155Event::TransferSingle)
+            },
+            Some(t) if *t == *ERC1155_TRANSFER_BATCH_TOPIC => {
+                ERC1155Decoder::decode_transfer_batch(log).map(ERC1155Event::TransferBatch)
+            },
+            Some(t) if *t == *ERC1155_APPROVAL_FOR_ALL_TOPIC => {
+                ERC1155Decoder::decode_approval_for_all(log).map(ERC1155Event::ApprovalForAll)
+            },
+            Some(t) if *t == *ERC1155_URI_TOPIC => ERC1155Decoder::decode_uri(log).map(ERC1155Event::URI),
+            Some(t) => Err(decode_error(format!("Not an ERC1155 event: {:?}", t))),
+            None => Err(decode_error("Expected log topic vec of length (1)".to_string()))
+        }
+    }
+
+    pub fn decode_transfer_single(log: &Log) -> Result<ERC1155TransferSingle, Error> {
+        let fields = ERC1155_ABI.decode_log("TransferSingle", log)?;
+
+        Ok(ERC1155TransferSingle {
+            token: log.address,
+            operator: fields.address("operator")?,
+            from: fields.address("from")?,
+            to: fields.address("to")?,
+            id: fields.uint("id")?,
+            value: fields.uint("value")?
+        })
+    }
+
+    /// Fails if the batch has a different number of ids and values.
+    pub fn decode_transfer_batch(log: &Log) -> Result<ERC1155TransferBatch, Error> {
+        let fields = ERC1155_ABI.decode_log("TransferBatch", log)?;
+        let ids = log_array::<U256>(&fields, "ids")?;
+        let values = log_array::<U256>(&fields, "values")?;
+        check_lengths(&ids, &values)?;
+
+        Ok(ERC1155TransferBatch {
+            token: log.address,
+            operator: fields.address("operator")?,
+            from: fields.address("from")?,
+            to: fields.address("to")?,
+            ids,
+            values
+        })
+    }
+
+    pub fn decode_approval_for_all(log: &Log) -> Result<ERC1155ApprovalForAll, Error> {
+        let fields = ERC1155_ABI.decode_log("ApprovalForAll", log)?;
+
+        Ok(ERC1155ApprovalForAll {
+            token: log.address,
+            account: fields.address("account")?,
+            operator: fields.address("operator")?,
+            approved: fields.boolean("approved")?
+        })
+    }
+
+    pub fn decode_uri(log: &Log) -> Result<ERC1155URI, Error> {
+        let fields = ERC1155_ABI.decode_log("URI", log)?;
+        let value = fields.get("value").and_then(AbiValue::to_str)
+            .ok_or_else(|| decode_error(format!("Missing URI value: {:?}", fields)))?;
+
+        Ok(ERC1155URI { token: log.address, id: fields.uint("id")?, value: value.to_string() })
+    }
+}
+
+fn call_args(name: &str, input: &str) -> Result<vec::IntoIter<AbiValue>, Error> {
+    let function = ERC1155_ABI.function(name).expect("ERC1155 function");
+    Ok(function.decode_input(input, DecodeMode::Strict)?.into_iter())
+}
+
+/// Converts each element of a decoded array.
+fn array<T: FromAbiValue>(value: AbiValue) -> Result<Vec<T>, Error> {
+    match value {
+        AbiValue::Array(values) => values.into_iter().map(T::from_abi).collect(),
+        other => Err(decode_error(format!("Expected an array, got {:?}", other)))
+    }
+}
+
+fn take_array<T: FromAbiValue>(args: &mut vec::IntoIter<AbiValue>) -> Result<Vec<T>, Error> {
+    take_value::<AbiValue, _>(args).and_then(array)
+}
+
+fn log_array<T: FromAbiValue>(fields: &DecodedLog, name: &str) -> Result<Vec<T>, Error> {
+    fields.get(name).cloned()
+        .ok_or_else(|| decode_error(format!("Missing {}: {:?}", name, fields)))
+        .and_then(array)
+}
+
+fn check_lengths<A, B>(a: &[A], b: &[B]) -> Result<(), Error> {
+    if a.len() != b.len() {
+        return Err(decode_error(format!("Batch arrays have different lengths: {} and {}", a.len(), b.len())));
+    }
+    Ok(())
+}
+
+fn decode_error(message: String) -> Error {
+    ErrorKind::Decoder(message).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use contracts::abi::{encode_hex, AbiValue};
+    use objects::fixtures::log;
+    use types::{H160, H256, U256};
+    use super::ERC1155Decoder;
+    use super::super::constants::*;
+    use super::super::models::*;
+
+    const TOKEN: &str = "76be3b62873462d2142405439777e971754e8e77";
+    const OPERATOR: &str = "8d12a197cb00d4747a1fe03395095ce2a5cc6819";
+    const OWNER: &str = "461680c41786ec889ac9de7f5e89a2f4e0fcb156";
+
+    fn word(hex: &str) -> H256 {
+        H256::from_str(&format!("{:0>64}", hex)).unwrap()
+    }
+
+    fn uints(values: &[u64]) -> AbiValue {
+        AbiValue::Array(values.iter().map(|v| AbiValue::Uint(U256::from(*v))).collect())
+    }
+
+    #[test]
+    fn decodes_transfer_events() {
+        let token = H160::from_str(TOKEN).unwrap();
+        let single = log(
+            token,
+            vec![*ERC1155_TRANSFER_SINGLE_TOPIC, word(OPERATOR), word("0"), word(OWNER)],
+            &format!("0x{:064x}{:064x}", 7, 100)
+        );
+        match ERC1155Decoder::decode_event(&single).unwrap() {
+            ERC1155Event::TransferSingle(transfer) => {
+                assert!(transfer.from.is_zero());
+                assert_eq!(transfer.to, H160::from_str(OWNER).unwrap());
+                assert_eq!((transfer.id, transfer.value), (U256::from(7), U256::from(100)));
+            },
+            other => panic!("Expected a single transfer, got {:?}", other)
+        }
+
+        let batch = log(
+            token,
+            vec![*ERC1155_TRANSFER_BATCH_TOPIC, word(OPERATOR), word(OWNER), word(OPERATOR)],
+            &encode_hex(&[uints(&[1, 2, 3]), uints(&[10, 20, 30])])
+        );
+        let batch = ERC1155Decoder::decode_transfer_batch(&batch).unwrap();
+        assert_eq!(batch.ids, vec![U256::from(1), U256::from(2), U256::from(3)]);
+        assert_eq!(batch.transfers()[2].value, U256::from(30));
+
+        let mismatched = log(
+            token,
+            vec![*ERC1155_TRANSFER_BATCH_TOPIC, word(OPERATOR), word(OWNER), word(OPERATOR)],
+            &encode_hex(&[uints(&[1, 2]), uints(&[10])])
+        );
+        assert!(ERC1155Decoder::decode_event(&mismatched).is_err());
+    }
+
+    #[test]
+    fn decodes_uri_events() {
+        let token = H160::from_str(TOKEN).unwrap();
+        let uri = log(
+            token,
+            vec![*ERC1155_URI_TOPIC, word("7")],
+            &encode_hex(&[AbiValue::String("ipfs://token/7".to_string())])
+        );
+        assert_eq!(
+            ERC1155Decoder::decode_event(&uri).unwrap(),
+            ERC1155Event::URI(ERC1155URI {
+                token,
+                id: U256::from(7),
+                value: "ipfs://token/7".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn decodes_batch_calls() {
+        let (owner, operator) = (H160::from_str(OWNER).unwrap(), H160::from_str(OPERATOR).unwrap());
+
+        let input = ERC1155_ABI.function("safeBatchTransferFrom").unwrap().encode_input_hex(&[
+            AbiValue::Address(owner), AbiValue::Address(operator), uints(&[1, 2]), uints(&[5, 6]), AbiValue::Bytes(vec![])
+        ]).unwrap();
+        assert_eq!(
+            ERC1155Decoder::decode_input(&input).unwrap(),
+            ERC1155Action::SafeBatchTransferFrom(
+                owner, operator, vec![U256::from(1), U256::from(2)], vec![U256::from(5), U256::from(6)], vec![]
+            )
+        );
+
+        let input = ERC1155_ABI.function("balanceOfBatch").unwrap().encode_input_hex(&[
+            AbiValue::Array(vec![AbiValue::Address(owner), AbiValue::Address(operator)]), uints(&[1, 2])
+        ]).unwrap();
+        assert_eq!(
+            ERC1155Decoder::decode_input(&input).unwrap(),
+            ERC1155Action::BalanceOfBatch(vec![owner, operator], vec![U256::from(1), U256::from(2)])
+        );
+
+        let input = ERC1155_ABI.function("balanceOfBatch").unwrap().encode_input_hex(&[
+            AbiValue::Array(vec![AbiValue::Address(owner)]), uints(&[1, 2])
+        ]).unwrap();
+        assert!(ERC1155Decoder::decode_input(&input).is_err());
+    }
+}
@@ -0,0 +1,14 @@
+pub mod constants;
+pub mod decoder;
+mod balances;
+mod models;
+
+pub use self::balances::BalanceTracker;
+pub use self::models::{
+    ERC1155Action,
+    ERC1155ApprovalForAll,
+    ERC1155Event,
+    ERC1155TransferBatch,
+    ERC1155TransferSingle,
+    ERC1155URI
+};
@@ -0,0 +1,122 @@
+use contracts::{ContractFunction, NamedFunction};
+use types::{H160, U256};
+
+type Id = U256;
+type Amount = U256;
+type Account = H160;
+type Operator = H160;
+type Recipient = H160;
+type Data = Vec<u8>;
+
+/// A transfer of an amount of one token id.  Mints are transfers from `0x0`, and burns
+/// transfers to `0x0`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ERC1155TransferSingle {
+    /// The token contract that emitted the event.
+    pub token: H160,
+    /// The address that made the transfer, either the owner or an approved operator.
+    pub operator: H160,
+    pub from: H160,
+    pub to: H160,
+    pub id: U256,
+    pub value: U256
+}
+
+/// A transfer of several token ids at once.  `ids` and `values` have the same length.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ERC1155TransferBatch {
+    /// The token contract that emitted the event.
+    pub token: H160,
+    pub operator: H160,
+    pub from: H160,
+    pub to: H160,
+    pub ids: Vec<U256>,
+    pub values: Vec<U256>
+}
+
+impl ERC1155TransferBatch {
+    /// The batch as one transfer per id, in order.
+    pub fn transfers(&self) -> Vec<ERC1155TransferSingle> {
+        self.ids.iter().zip(&self.values)
+            .map(|(id, value)| ERC1155TransferSingle {
+                token: self.token,
+                operator: self.operator,
+                from: self.from,
+                to: self.to,
+                id: *id,
+                value: *value
+            })
+            .collect()
+    }
+}
+
+/// An operator allowed, or no longer allowed, to transfer all of the account's tokens.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ERC1155ApprovalForAll {
+    /// The token contract that emitted the event.
+    pub token: H160,
+    pub account: H160,
+    pub operator: H160,
+    pub approved: bool
+}
+
+/// A change of the metadata URI of a token id.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ERC1155URI {
+    /// The token contract that emitted the event.
+    pub token: H160,
+    pub id: U256,
+    pub value: String
+}
+
+/// An event emitted by an ERC1155 token contract.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ERC1155Event {
+    TransferSingle(ERC1155TransferSingle),
+    TransferBatch(ERC1155TransferBatch),
+    ApprovalForAll(ERC1155ApprovalForAll),
+    URI(ERC1155URI)
+}
+
+/// A call to an ERC1155 token contract.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ERC1155Action {
+    /// A transfer of an amount of one id, with data passed on to a contract recipient.
+    SafeTransferFrom(Account, Recipient, Id, Amount, Data),
+    /// A transfer of amounts of several ids.
+    SafeBatchTransferFrom(Account, Recipient, Vec<Id>, Vec<Amount>, Data),
+    /// Allows, or disallows, an operator to transfer all of the sender's tokens.
+    SetApprovalForAll(Operator, bool),
+    /// Constant call returning the account's balance of an id.
+    BalanceOf(Account, Id),
+    /// Constant call returning the balance of each account and id pair.
+    BalanceOfBatch(Vec<Account>, Vec<Id>),
+    /// Constant call checking whether the operator may transfer all of the account's tokens.
+    IsApprovedForAll(Account, Operator),
+    /// Constant call returning the metadata URI of an id.
+    Uri(Id)
+}
+
+impl NamedFunction for ERC1155Action {
+    fn get_function(&self) -> ContractFunction {
+        match *self {
+            ERC1155Action::SafeTransferFrom(_, _, _, _, _) => ContractFunction::Mutable(
+                "ERC1155.safeTransferFrom".to_string()
+            ),
+            ERC1155Action::SafeBatchTransferFrom(_, _, _, _, _) => ContractFunction::Mutable(
+                "ERC1155.safeBatchTransferFrom".to_string()
+            ),
+            ERC1155Action::SetApprovalForAll(_, _) => ContractFunction::Mutable(
+                "ERC1155.setApprovalForAll".to_string()
+            ),
+            ERC1155Action::BalanceOf(_, _) => ContractFunction::Immutable("ERC1155.balanceOf".to_string()),
+            ERC1155Action::BalanceOfBatch(_, _) => ContractFunction::Immutable(
+                "ERC1155.balanceOfBatch".to_string()
+            ),
+            ERC1155Action::IsApprovedForAll(_, _) => ContractFunction::Immutable(
+                "ERC1155.isApprovedForAll".to_string()
+            ),
+            ERC1155Action::Uri(_) => ContractFunction::Immutable("ERC1155.uri".to_string())
+        }
+    }
+}
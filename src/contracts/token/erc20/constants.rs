@@ -0,0 +1,89 @@
+use contracts::abi::Contract;
+use types::H256;
+
+lazy_static! {
+    /// The events and functions of the ERC20 standard.
+    pub static ref ERC20_ABI: Contract = Contract::from_signatures(vec![
+        "event Transfer(address indexed from, address indexed to, uint256 value)",
+        "event Approval(address indexed owner, address indexed spender, uint256 value)",
+        "function transfer(address to, uint256 value) returns (bool)",
+        "function transferFrom(address from, address to, uint256 value) returns (bool)",
+        "function approve(address spender, uint256 value) returns (bool)",
+        "function increaseAllowance(address spender, uint256 addedValue) returns (bool)",
+        "function decreaseAllowance(address spender, uint256 subtractedValue) returns (bool)",
+        "function balanceOf(address owner) view returns (uint256)",
+        "function allowance(address owner, address spender) view returns (uint256)",
+        "function totalSupply() view returns (uint256)",
+        "function decimals() view returns (uint8)",
+        "function symbol() view returns (string)",
+        "function name() view returns (string)"
+    ]).expect("Invalid ERC20 ABI");
+
+    /// Shared with ERC721's `Transfer`, which indexes its third parameter.
+    pub static ref ERC20_TRANSFER_TOPIC: H256 = event_topic("Transfer");
+    /// Shared with ERC721's `Approval`, which indexes its third parameter.
+    pub static ref ERC20_APPROVAL_TOPIC: H256 = event_topic("Approval");
+
+    // Methods
+    pub static ref TRANSFER_ID: String = method_id("transfer");
+    pub static ref TRANSFER_FROM_ID: String = method_id("transferFrom");
+    pub static ref APPROVE_ID: String = method_id("approve");
+    // Not part of the standard, but implemented by OpenZeppelin's tokens.
+    pub static ref INCREASE_ALLOWANCE_ID: String = method_id("increaseAllowance");
+    pub static ref DECREASE_ALLOWANCE_ID: String = method_id("decreaseAllowance");
+
+    // Static methods
+    pub static ref BALANCE_OF_ID: String = method_id("balanceOf");
+    pub static ref ALLOWANCE_ID: String = method_id("allowance");
+    pub static ref TOTAL_SUPPLY_ID: String = method_id("totalSupply");
+    pub static ref DECIMALS_ID: String = method_id("decimals");
+    pub static ref SYMBOL_ID: String = method_id("symbol");
+    pub static ref NAME_ID: String = method_id("name");
+}
+
+fn method_id(name: &str) -> String {
+    ERC20_ABI.function(name)
+        .map(|f| f.method_id())
+        .unwrap_or_else(|| panic!("ERC20 ABI is missing function {}", name))
+}
+
+fn event_topic(name: &str) -> H256 {
+    ERC20_ABI.event(name)
+        .map(|e| e.topic())
+        .unwrap_or_else(|| panic!("ERC20 ABI is missing event {}", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use types::H256;
+    use super::*;
+
+    #[test]
+    fn topics_match_standard() {
+        assert_eq!(
+            *ERC20_TRANSFER_TOPIC,
+            H256::from_str("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef").unwrap()
+        );
+        assert_eq!(
+            *ERC20_APPROVAL_TOPIC,
+            H256::from_str("8c5be1e5ebec7d5bd14f71427d1e84f3dd0314c0f7b2291e5b200ac8c7c3b925").unwrap()
+        );
+    }
+
+    #[test]
+    fn method_ids_match_standard() {
+        assert_eq!(*TRANSFER_ID, "0xa9059cbb");
+        assert_eq!(*TRANSFER_FROM_ID, "0x23b872dd");
+        assert_eq!(*APPROVE_ID, "0x095ea7b3");
+        assert_eq!(*INCREASE_ALLOWANCE_ID, "0x39509351");
+        assert_eq!(*DECREASE_ALLOWANCE_ID, "0xa457c2d7");
+        assert_eq!(*BALANCE_OF_ID, "0x70a08231");
+        assert_eq!(*ALLOWANCE_ID, "0xdd62ed3e");
+        assert_eq!(*TOTAL_SUPPLY_ID, "0x18160ddd");
+        assert_eq!(*DECIMALS_ID, "0x313ce567");
+        assert_eq!(*SYMBOL_ID, "0x95d89b41");
+        assert_eq!(*NAME_ID, "0x06fdde03");
+    }
+}
@@ -0,0 +1,289 @@
+use std::vec;
+
+use contracts::abi::{decode, hex_to_bytes, take_value, AbiValue, DecodeMode, ParamType};
+use error::{Error, ErrorKind};
+use objects::{Log, ParityTrace, Transaction};
+use types::{H160, U256};
+
+use super::constants::*;
+use super::models::*;
+
+/// Decoder for the calls to and events of any ERC20 token contract.
+pub struct ERC20Decoder;
+
+impl ERC20Decoder {
+    pub fn decode_action(tx: &Transaction) -> Result<ERC20Action, Error> {
+        ERC20Decoder::decode_input(&tx.input)
+    }
+
+    /// Decodes calls made by other contracts, such as EtherDelta's `depositToken` pulling
+    /// tokens with `transferFrom`.
+    pub fn decode_trace_action(trace: &ParityTrace) -> Result<ERC20Action, Error> {
+        ERC20Decoder::decode_input(&trace.action.input)
+    }
+
+    pub fn decode_input(input: &str) -> Result<ERC20Action, Error> {
+        let method_id = match input.get(..10) {
+            Some(id) => id.to_lowercase(),
+            None => return Err(decode_error(format!("Input is too short for an ERC20 call: {}", input)))
+        };
+
+        match method_id.as_str() {
+            id if id == *TRANSFER_ID => {
+                let mut args = call_args("transfer", input)?;
+                Ok(ERC20Action::Transfer(take_value(&mut args)?, take_value(&mut args)?))
+            },
+            id if id == *TRANSFER_FROM_ID => {
+                let mut args = call_args("transferFrom", input)?;
+                Ok(ERC20Action::TransferFrom(take_value(&mut args)?, take_value(&mut args)?, take_value(&mut args)?))
+            },
+            id if id == *APPROVE_ID => {
+                let mut args = call_args("approve", input)?;
+                Ok(ERC20Action::Approve(take_value(&mut args)?, take_value(&mut args)?))
+            },
+            id if id == *INCREASE_ALLOWANCE_ID => {
+                let mut args = call_args("increaseAllowance", input)?;
+                Ok(ERC20Action::IncreaseAllowance(take_value(&mut args)?, take_value(&mut args)?))
+            },
+            id if id == *DECREASE_ALLOWANCE_ID => {
+                let mut args = call_args("decreaseAllowance", input)?;
+                Ok(ERC20Action::DecreaseAllowance(take_value(&mut args)?, take_value(&mut args)?))
+            },
+            id if id == *BALANCE_OF_ID => {
+                let mut args = call_args("balanceOf", input)?;
+                Ok(ERC20Action::BalanceOf(take_value(&mut args)?))
+            },
+            id if id == *ALLOWANCE_ID => {
+                let mut args = call_args("allowance", input)?;
+                Ok(ERC20Action::Allowance(take_value(&mut args)?, take_value(&mut args)?))
+            },
+            id if id == *TOTAL_SUPPLY_ID => call_args("totalSupply", input).map(|_| ERC20Action::TotalSupply),
+            id if id == *DECIMALS_ID => call_args("decimals", input).map(|_| ERC20Action::Decimals),
+            id if id == *SYMBOL_ID => call_args("symbol", input).map(|_| ERC20Action::Symbol),
+            id if id == *NAME_ID => call_args("name", input).map(|_| ERC20Action::Name),
+            _ => Err(decode_error(format!("Not an ERC20 method: {}", method_id)))
+        }
+    }
+
+    pub fn decode_event(log: &Log) -> Result<ERC20Event, Error> {
+        match log.topics.first() {
+            Some(t) if *t == *ERC20_TRANSFER_TOPIC => {
+                ERC20Decoder::decode_transfer(log).map(ERC20Event::Transfer)
+            },
+            Some(t) if *t == *ERC20_APPROVAL_TOPIC => {
+                ERC20Decoder::decode_approval(log).map(ERC20Event::Approval)
+            },
+            Some(t) => Err(ErrorKind::Decoder(format!("Not an ERC20 event: {:?}", t)).into()),
+            None => Err(ErrorKind::Decoder("Expected log topic vec of length (1)".to_string()).into())
+        }
+    }
+
+    pub fn decode_transfer(log: &Log) -> Result<ERC20Transfer, Error> {
+        let (from, to, value) = decode_params("Transfer", log)?;
+        Ok(ERC20Transfer { token: log.address, from, to, value })
+    }
+
+    pub fn decode_approval(log: &Log) -> Result<ERC20Approval, Error> {
+        let (owner, spender, value) = decode_params("Approval", log)?;
+        Ok(ERC20Approval { token: log.address, owner, spender, value })
+    }
+}
+
+/// Decodes the `(address, address, uint256)` parameters shared by both events.
+///
+/// Some early tokens don't index the addresses, so the parameters that aren't in the topics
+/// are read from the data, and any data after them is ignored.  ERC721 emits events with the
+/// same topics but indexes the token id as well, so logs with a fourth topic are rejected.
+/// ERC721 contracts that predate the standard, like CryptoKitties, don't index anything and
+/// can't be told apart from an ERC20 log.
+fn decode_params(name: &str, log: &Log) -> Result<(H160, H160, U256), Error> {
+    let event = ERC20_ABI.event(name).expect("ERC20 event");
+    if log.topics.first() != Some(&event.topic()) {
+        return Err(decode_error(format!("Log is not an ERC20 {} event: {:?}", name, log.topics)));
+    }
+
+    let indexed = log.topics.len() - 1;
+    if indexed == 3 {
+        return Err(decode_error(format!("{} log has an indexed token id, it's from an ERC721 contract", name)));
+    } else if indexed > 3 {
+        return Err(decode_error(format!("{} log has {} topics", name, log.topics.len())));
+    }
+
+    let types = [ParamType::Address, ParamType::Address, ParamType::Uint(256)];
+    let mut values = Vec::with_capacity(types.len());
+    for (kind, topic) in types.iter().zip(&log.topics[1..]) {
+        values.push(decode(::std::slice::from_ref(kind), &topic.0, DecodeMode::Strict)?.remove(0));
+    }
+
+    let data = hex_to_bytes(&log.data)?;
+    let data_size = (types.len() - indexed) * 32;
+    if data.len() < data_size {
+        return Err(decode_error(format!("{} log data is too short: {} bytes", name, data.len())));
+    }
+    values.extend(decode(&types[indexed..], &data[..data_size], DecodeMode::Strict)?);
+
+    match (values[0].to_address(), values[1].to_address(), values[2].to_uint()) {
+        (Some(a), Some(b), Some(value)) => Ok((a, b, value)),
+        _ => Err(decode_error(format!("Unexpected {} values: {:?}", name, values)))
+    }
+}
+
+fn call_args(name: &str, input: &str) -> Result<vec::IntoIter<AbiValue>, Error> {
+    let function = ERC20_ABI.function(name).expect("ERC20 function");
+    Ok(function.decode_input(input, DecodeMode::Strict)?.into_iter())
+}
+
+fn decode_error(message: String) -> Error {
+    ErrorKind::Decoder(message).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use serde_json;
+
+    use contracts::{ContractFunction, NamedFunction};
+    use contracts::abi::AbiValue;
+    use objects::fixtures::{log, proxy_traces};
+    use objects::{Block, BlockTx};
+    use types::{H160, H256, U256};
+    use super::ERC20Decoder;
+    use super::super::constants::*;
+    use super::super::models::*;
+
+    const ZRX: &str = "e41d2489571d322189246dafa5ebde1f4699f498";
+    const FROM: &str = "461680c41786ec889ac9de7f5e89a2f4e0fcb156";
+    const TO: &str = "8d12a197cb00d4747a1fe03395095ce2a5cc6819";
+
+    fn word(address: &str) -> H256 {
+        H256::from_str(&format!("{:0>64}", address)).unwrap()
+    }
+
+    fn transfer() -> ERC20Transfer {
+        ERC20Transfer {
+            token: H160::from_str(ZRX).unwrap(),
+            from: H160::from_str(FROM).unwrap(),
+            to: H160::from_str(TO).unwrap(),
+            value: U256::from(1000)
+        }
+    }
+
+    #[test]
+    fn decodes_standard_events() {
+        let zrx = H160::from_str(ZRX).unwrap();
+        let transfer_log = log(
+            zrx,
+            vec![*ERC20_TRANSFER_TOPIC, word(FROM), word(TO)],
+            &format!("0x{:064x}", 1000)
+        );
+        assert_eq!(ERC20Decoder::decode_event(&transfer_log).unwrap(), ERC20Event::Transfer(transfer()));
+
+        let approval_log = log(
+            zrx,
+            vec![*ERC20_APPROVAL_TOPIC, word(FROM), word(TO)],
+            &format!("0x{:064x}", 1000)
+        );
+        match ERC20Decoder::decode_event(&approval_log).unwrap() {
+            ERC20Event::Approval(approval) => {
+                assert_eq!(approval.owner, H160::from_str(FROM).unwrap());
+                assert_eq!(approval.spender, H160::from_str(TO).unwrap());
+                assert_eq!(approval.value, U256::from(1000));
+            },
+            other => panic!("Expected an approval, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn decodes_non_standard_transfers() {
+        let zrx = H160::from_str(ZRX).unwrap();
+        let unindexed = log(
+            zrx,
+            vec![*ERC20_TRANSFER_TOPIC],
+            &format!("0x{:0>64}{:0>64}{:064x}", FROM, TO, 1000)
+        );
+        assert_eq!(ERC20Decoder::decode_transfer(&unindexed).unwrap(), transfer());
+
+        let extra_data = log(
+            zrx,
+            vec![*ERC20_TRANSFER_TOPIC, word(FROM)],
+            &format!("0x{:0>64}{:064x}{:064x}", TO, 1000, 7)
+        );
+        assert_eq!(ERC20Decoder::decode_transfer(&extra_data).unwrap(), transfer());
+    }
+
+    #[test]
+    fn rejects_erc721_and_malformed_logs() {
+        let zrx = H160::from_str(ZRX).unwrap();
+        let erc721 = log(
+            zrx,
+            vec![*ERC20_TRANSFER_TOPIC, word(FROM), word(TO), word("1")],
+            "0x"
+        );
+        assert!(ERC20Decoder::decode_event(&erc721).is_err());
+
+        let short = log(zrx, vec![*ERC20_TRANSFER_TOPIC, word(FROM)], &format!("0x{:0>64}", TO));
+        assert!(ERC20Decoder::decode_event(&short).is_err());
+
+        let dirty_address = log(
+            zrx,
+            vec![*ERC20_TRANSFER_TOPIC, word(&format!("ff{:0>62}", FROM)), word(TO)],
+            &format!("0x{:064x}", 1000)
+        );
+        assert!(ERC20Decoder::decode_event(&dirty_address).is_err());
+
+        assert!(ERC20Decoder::decode_approval(&log(zrx, vec![*ERC20_TRANSFER_TOPIC], "0x")).is_err());
+        assert!(ERC20Decoder::decode_event(&log(zrx, vec![], "0x")).is_err());
+    }
+
+    #[test]
+    fn decodes_transfers_in_transactions() {
+        let block = serde_json::from_str::<Block>(include_str!("../../../../test_data/block_with_tx.json")).unwrap();
+        let actions = block.transactions.iter()
+            .filter_map(|tx| match *tx {
+                BlockTx::Tx(ref tx) => ERC20Decoder::decode_action(tx).ok(),
+                BlockTx::Hash(_) => None
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(actions.len(), 3);
+        assert_eq!(
+            actions[0],
+            ERC20Action::Transfer(H160::from_str("262209abd9a9c25d4e731c9fff2868a7817e4820").unwrap(), U256::from(100))
+        );
+        match actions[0].get_function() {
+            ContractFunction::Mutable(name) => assert_eq!(name, "ERC20.transfer"),
+            _ => panic!("transfer is not constant")
+        }
+    }
+
+    #[test]
+    fn decodes_traced_calls() {
+        let mut trace = proxy_traces().remove(0);
+        let (from, to) = (H160::from_str(FROM).unwrap(), H160::from_str(TO).unwrap());
+
+        trace.action.input = ERC20_ABI.function("transferFrom").unwrap()
+            .encode_input_hex(&[AbiValue::Address(from), AbiValue::Address(to), AbiValue::Uint(U256::from(5))])
+            .unwrap();
+        assert_eq!(
+            ERC20Decoder::decode_trace_action(&trace).unwrap(),
+            ERC20Action::TransferFrom(from, to, U256::from(5))
+        );
+
+        trace.action.input = format!("{}{:0>64}{:0>64}", *ALLOWANCE_ID, FROM, TO);
+        assert_eq!(ERC20Decoder::decode_trace_action(&trace).unwrap(), ERC20Action::Allowance(from, to));
+
+        trace.action.input = DECIMALS_ID.to_uppercase().replace("0X", "0x");
+        match ERC20Decoder::decode_trace_action(&trace).unwrap().get_function() {
+            ContractFunction::Immutable(name) => assert_eq!(name, "ERC20.decimals"),
+            _ => panic!("decimals is constant")
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_calls() {
+        assert!(ERC20Decoder::decode_input("0xa9059c").is_err());
+        assert!(ERC20Decoder::decode_input("0xdeadbeef").is_err());
+        assert!(ERC20Decoder::decode_input(&format!("{}{:0>64}", *TRANSFER_ID, TO)).is_err());
+    }
+}
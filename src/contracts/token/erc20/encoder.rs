@@ -0,0 +1,150 @@
+use contracts::abi::{decode, hex_to_bytes, AbiValue, DecodeMode, Function, ParamType};
+use error::{Error, ErrorKind};
+use objects::TransactionCall;
+use types::{H160, U256};
+
+use super::constants::*;
+
+/// Builds `eth_call`s against an ERC20 token's constant functions, and decodes their results.
+pub struct ERC20Encoder;
+
+impl ERC20Encoder {
+    pub fn name(token: H160) -> Result<TransactionCall, Error> {
+        ERC20Encoder::call(token, "name", &[])
+    }
+
+    pub fn symbol(token: H160) -> Result<TransactionCall, Error> {
+        ERC20Encoder::call(token, "symbol", &[])
+    }
+
+    pub fn decimals(token: H160) -> Result<TransactionCall, Error> {
+        ERC20Encoder::call(token, "decimals", &[])
+    }
+
+    pub fn total_supply(token: H160) -> Result<TransactionCall, Error> {
+        ERC20Encoder::call(token, "totalSupply", &[])
+    }
+
+    pub fn balance_of(token: H160, owner: H160) -> Result<TransactionCall, Error> {
+        ERC20Encoder::call(token, "balanceOf", &[AbiValue::Address(owner)])
+    }
+
+    pub fn allowance(token: H160, owner: H160, spender: H160) -> Result<TransactionCall, Error> {
+        ERC20Encoder::call(token, "allowance", &[AbiValue::Address(owner), AbiValue::Address(spender)])
+    }
+
+    /// Decodes a `name()` result.  Tokens such as MKR return a `bytes32` instead of a
+    /// `string`, padded with zeros.
+    pub fn decode_name(output: &str) -> Result<String, Error> {
+        decode_text("name", output)
+    }
+
+    /// Decodes a `symbol()` result, returned as a `bytes32` by tokens such as MKR.
+    pub fn decode_symbol(output: &str) -> Result<String, Error> {
+        decode_text("symbol", output)
+    }
+
+    /// Decodes a `decimals()` result.  Some tokens declare it as a `uint256`, which is accepted
+    /// as long as it fits in a `uint8`.
+    pub fn decode_decimals(output: &str) -> Result<u8, Error> {
+        let decimals = decode_uint("decimals", output)?;
+        if decimals > U256::from(u8::MAX) {
+            return Err(ErrorKind::Decoder(format!("Decimals out of range: {}", decimals)).into());
+        }
+        Ok(decimals.low_u64() as u8)
+    }
+
+    pub fn decode_total_supply(output: &str) -> Result<U256, Error> {
+        decode_uint("totalSupply", output)
+    }
+
+    pub fn decode_balance_of(output: &str) -> Result<U256, Error> {
+        decode_uint("balanceOf", output)
+    }
+
+    pub fn decode_allowance(output: &str) -> Result<U256, Error> {
+        decode_uint("allowance", output)
+    }
+
+    fn call(token: H160, name: &str, args: &[AbiValue]) -> Result<TransactionCall, Error> {
+        TransactionCall::function_call(token, function(name)?, args)
+    }
+}
+
+fn function(name: &str) -> Result<&'static Function, Error> {
+    ERC20_ABI.function(name)
+        .ok_or_else(|| ErrorKind::Abi(format!("Unknown ERC20 function: {}", name)).into())
+}
+
+fn decode_uint(name: &str, output: &str) -> Result<U256, Error> {
+    let data = hex_to_bytes(output)?;
+    if data.len() != 32 {
+        return Err(ErrorKind::Decoder(format!("Unexpected {} result: {}", name, output)).into());
+    }
+
+    decode(&[ParamType::Uint(256)], &data, DecodeMode::Strict)?
+        .first()
+        .and_then(AbiValue::to_uint)
+        .ok_or_else(|| ErrorKind::Decoder(format!("Empty {} result: {}", name, output)).into())
+}
+
+/// A `string`, or a single `bytes32` word holding the text followed by zeros.  Invalid UTF-8
+/// is replaced rather than rejected.
+fn decode_text(name: &str, output: &str) -> Result<String, Error> {
+    let data = hex_to_bytes(output)?;
+
+    if data.len() == 32 {
+        let end = data.iter().position(|b| *b == 0).unwrap_or(data.len());
+        return Ok(String::from_utf8_lossy(&data[..end]).trim().to_string());
+    }
+
+    decode(&[ParamType::String], &data, DecodeMode::Lenient)?
+        .first()
+        .and_then(|v| v.to_str().map(|s| s.trim_end_matches('\0').to_string()))
+        .ok_or_else(|| ErrorKind::Decoder(format!("Empty {} result: {}", name, output)).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use contracts::abi::{encode_hex, AbiValue};
+    use types::{H160, U256};
+    use super::ERC20Encoder;
+
+    const MKR_SYMBOL: &str = "0x4d4b520000000000000000000000000000000000000000000000000000000000";
+
+    #[test]
+    fn encodes_calls() {
+        let token = H160::from_str("e41d2489571d322189246dafa5ebde1f4699f498").unwrap();
+        let owner = H160::from_str("461680c41786ec889ac9de7f5e89a2f4e0fcb156").unwrap();
+
+        let call = ERC20Encoder::decimals(token).unwrap();
+        assert_eq!(call.to, Some(token));
+        assert_eq!(call.data, Some("0x313ce567".to_string()));
+        assert_eq!(
+            ERC20Encoder::balance_of(token, owner).unwrap().data,
+            Some(format!("0x70a08231{:0>64}", "461680c41786ec889ac9de7f5e89a2f4e0fcb156"))
+        );
+    }
+
+    #[test]
+    fn decodes_string_and_bytes32_text() {
+        let zrx = encode_hex(&[AbiValue::String("0x Protocol Token".to_string())]);
+
+        assert_eq!(ERC20Encoder::decode_name(&zrx).unwrap(), "0x Protocol Token");
+        assert_eq!(ERC20Encoder::decode_symbol(MKR_SYMBOL).unwrap(), "MKR");
+        assert!(ERC20Encoder::decode_symbol("0x").is_err());
+    }
+
+    #[test]
+    fn decodes_numbers() {
+        assert_eq!(ERC20Encoder::decode_decimals(&format!("0x{:064x}", 18)).unwrap(), 18);
+        assert!(ERC20Encoder::decode_decimals(&format!("0x{:064x}", 256)).is_err());
+        assert!(ERC20Encoder::decode_decimals("0x").is_err());
+        assert_eq!(
+            ERC20Encoder::decode_total_supply(&format!("0x{:064x}", 1_000_000)).unwrap(),
+            U256::from(1_000_000)
+        );
+    }
+}
@@ -0,0 +1,288 @@
+use std::collections::{BTreeMap, HashMap};
+
+use error::{Error, ErrorKind};
+use objects::Log;
+use types::{H160, U256};
+
+use super::decoder::ERC20Decoder;
+use super::models::ERC20Transfer;
+
+/// Position of a log in the chain: block number, transaction index and log index.
+pub type LogPosition = (u64, u64, u64);
+
+/// ERC20 balances of every `(token, holder)` pair seen in a replay of `Transfer` events.
+///
+/// Transfers must be applied in chain order, from the tokens' creation.  The transfers of
+/// each block are kept so that blocks dropped by a reorg can be rolled back, until `prune`
+/// forgets the blocks that are final.  `snapshot` copies the balances at the end of a block,
+/// which `balance_at` reads back later, so holder balances at past blocks don't need an
+/// archive node.
+#[derive(Debug, Clone, Default)]
+pub struct ERC20Ledger {
+    balances: HashMap<(H160, H160), U256>,
+    position: Option<LogPosition>,
+    /// Position of the last pruned transfer, which a rollback can't go back past.
+    floor: Option<LogPosition>,
+    /// Applied transfers by block, in the order they were applied.
+    journal: BTreeMap<u64, Vec<(LogPosition, ERC20Transfer)>>,
+    snapshots: BTreeMap<u64, HashMap<(H160, H160), U256>>
+}
+
+impl ERC20Ledger {
+    pub fn new() -> ERC20Ledger {
+        ERC20Ledger::default()
+    }
+
+    /// Moves the value from the sender to the recipient.  Mints (from `0x0`) only credit the
+    /// recipient and burns (to `0x0`) only debit the sender.  The transfer must come after
+    /// every transfer applied so far, and after the last snapshot's block.
+    pub fn apply(&mut self, position: LogPosition, transfer: &ERC20Transfer) -> Result<(), Error> {
+        if let Some(last) = self.position {
+            if position <= last {
+                return Err(ErrorKind::Amount(format!(
+                    "Transfer at {:?} is out of order, the ledger is at {:?}", position, last
+                )).into());
+            }
+        }
+        if let Some(block) = self.snapshots.keys().next_back() {
+            if position.0 <= *block {
+                return Err(ErrorKind::Amount(format!(
+                    "Transfer at {:?} is in block {}, which was already snapshotted", position, block
+                )).into());
+            }
+        }
+
+        self.transfer(transfer)?;
+        self.position = Some(position);
+        self.journal.entry(position.0).or_default().push((position, transfer.clone()));
+        Ok(())
+    }
+
+    /// Decodes and applies an ERC20 `Transfer` log at its position in the chain.
+    pub fn apply_log(&mut self, log: &Log) -> Result<(), Error> {
+        let position = match (log.block_number, log.transaction_index, log.log_index) {
+            (Some(block), Some(tx), Some(index)) => (block.low_u64(), tx.low_u64(), index.low_u64()),
+            _ => return Err(ErrorKind::Decoder("Log has no position in the chain".to_string()).into())
+        };
+        if log.log_type == "removed" {
+            return Err(ErrorKind::Decoder(format!(
+                "Log at {:?} was removed, roll back its block instead", position
+            )).into());
+        }
+        let transfer = ERC20Decoder::decode_transfer(log)?;
+        self.apply(position, &transfer)
+    }
+
+    /// Copies the current balances as the balances at the end of `block`.  No transfer from
+    /// `block` or an earlier block can be applied afterwards.
+    pub fn snapshot(&mut self, block: u64) -> Result<(), Error> {
+        if let Some((last, _, _)) = self.position {
+            if block < last {
+                return Err(ErrorKind::Amount(format!(
+                    "Can't snapshot block {}, the ledger is at block {}", block, last
+                )).into());
+            }
+        }
+        self.snapshots.insert(block, self.balances.clone());
+        Ok(())
+    }
+
+    /// Reverts the transfers of `block` and every later block, most recent first, and drops
+    /// their snapshots.  Returns the number of transfers reverted.
+    pub fn rollback(&mut self, block: u64) -> usize {
+        let removed = self.journal.split_off(&block);
+        let mut reverted = 0;
+
+        for (_, transfers) in removed.into_iter().rev() {
+            for (_, transfer) in transfers.into_iter().rev() {
+                self.transfer(&ERC20Transfer { from: transfer.to, to: transfer.from, ..transfer })
+                    .expect("reverting an applied transfer");
+                reverted += 1;
+            }
+        }
+
+        self.snapshots.split_off(&block);
+        self.position = self.journal.values().next_back()
+            .and_then(|transfers| transfers.last())
+            .map(|&(position, _)| position)
+            .or(self.floor);
+        reverted
+    }
+
+    /// Forgets the transfers of the blocks before `block`, which can no longer be rolled back.
+    pub fn prune(&mut self, block: u64) {
+        let kept = self.journal.split_off(&block);
+        if let Some(&(position, _)) = self.journal.values().next_back().and_then(|transfers| transfers.last()) {
+            self.floor = Some(position);
+        }
+        self.journal = kept;
+    }
+
+    pub fn balance_of(&self, token: &H160, holder: &H160) -> U256 {
+        self.balances.get(&(*token, *holder)).cloned().unwrap_or_default()
+    }
+
+    /// The balance at the end of `block`, from the latest snapshot at or before it.  `None` if
+    /// there is no such snapshot.
+    pub fn balance_at(&self, token: &H160, holder: &H160, block: u64) -> Option<U256> {
+        self.snapshots.range(..=block).next_back()
+            .map(|(_, balances)| balances.get(&(*token, *holder)).cloned().unwrap_or_default())
+    }
+
+    /// The holders of a token with a non-zero balance, by descending balance.
+    pub fn holders_of(&self, token: &H160) -> Vec<(H160, U256)> {
+        let mut holders = self.balances.iter()
+            .filter(|&(&(t, _), _)| t == *token)
+            .map(|(&(_, holder), balance)| (holder, *balance))
+            .collect::<Vec<_>>();
+        holders.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        holders
+    }
+
+    /// Position of the last applied transfer.
+    pub fn position(&self) -> Option<LogPosition> {
+        self.position
+    }
+
+    fn transfer(&mut self, transfer: &ERC20Transfer) -> Result<(), Error> {
+        let from = (transfer.token, transfer.from);
+        let to = (transfer.token, transfer.to);
+
+        let debited = if transfer.from.is_zero() {
+            None
+        } else {
+            let balance = self.balances.get(&from).cloned().unwrap_or_default();
+            Some(balance.checked_sub(transfer.value).ok_or_else(|| {
+                Error::from(ErrorKind::Amount(format!(
+                    "{:?} holds {}, can't send {}", transfer.from, balance, transfer.value
+                )))
+            })?)
+        };
+
+        let credited = if transfer.to.is_zero() {
+            None
+        } else {
+            let balance = match debited {
+                Some(debited) if from == to => debited,
+                _ => self.balances.get(&to).cloned().unwrap_or_default()
+            };
+            Some(balance.checked_add(transfer.value).ok_or_else(|| {
+                Error::from(ErrorKind::Amount(format!("Balance of {:?} overflows", transfer.to)))
+            })?)
+        };
+
+        if let Some(balance) = debited {
+            self.set(from, balance);
+        }
+        if let Some(balance) = credited {
+            self.set(to, balance);
+        }
+        Ok(())
+    }
+
+    fn set(&mut self, key: (H160, H160), balance: U256) {
+        if balance.is_zero() {
+            self.balances.remove(&key);
+        } else {
+            self.balances.insert(key, balance);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use objects::Log;
+    use types::{H160, H256, U256};
+    use super::ERC20Ledger;
+    use super::super::constants::ERC20_TRANSFER_TOPIC;
+    use super::super::models::ERC20Transfer;
+
+    fn transfer(from: u64, to: u64, value: u64) -> ERC20Transfer {
+        ERC20Transfer { token: H160::from(1), from: H160::from(from), to: H160::from(to), value: U256::from(value) }
+    }
+
+    fn balance(ledger: &ERC20Ledger, holder: u64) -> U256 {
+        ledger.balance_of(&H160::from(1), &H160::from(holder))
+    }
+
+    #[test]
+    fn applies_transfers_in_order() {
+        let mut ledger = ERC20Ledger::new();
+        ledger.apply((10, 0, 0), &transfer(0, 5, 100)).unwrap();
+        ledger.apply((10, 0, 1), &transfer(5, 6, 30)).unwrap();
+        ledger.apply((11, 2, 7), &transfer(6, 0, 10)).unwrap();
+
+        assert_eq!(balance(&ledger, 5), U256::from(70));
+        assert_eq!(balance(&ledger, 6), U256::from(20));
+        assert_eq!(balance(&ledger, 0), U256::zero());
+        assert_eq!(ledger.holders_of(&H160::from(1)), vec![(H160::from(5), U256::from(70)), (H160::from(6), U256::from(20))]);
+
+        assert!(ledger.apply((11, 2, 7), &transfer(5, 6, 1)).is_err());
+        assert!(ledger.apply((11, 1, 9), &transfer(5, 6, 1)).is_err());
+        assert!(ledger.apply((12, 0, 0), &transfer(6, 5, 21)).is_err());
+        assert_eq!(ledger.position(), Some((11, 2, 7)));
+    }
+
+    #[test]
+    fn snapshots_and_rolls_back_blocks() {
+        let mut ledger = ERC20Ledger::new();
+        ledger.apply((10, 0, 0), &transfer(0, 5, 100)).unwrap();
+        ledger.snapshot(10).unwrap();
+        ledger.apply((11, 0, 0), &transfer(5, 6, 40)).unwrap();
+        ledger.snapshot(12).unwrap();
+        ledger.apply((13, 0, 0), &transfer(6, 7, 15)).unwrap();
+        ledger.apply((13, 1, 0), &transfer(5, 7, 5)).unwrap();
+
+        let (token, holder) = (H160::from(1), H160::from(5));
+        assert_eq!(ledger.balance_at(&token, &holder, 9), None);
+        assert_eq!(ledger.balance_at(&token, &holder, 10), Some(U256::from(100)));
+        assert_eq!(ledger.balance_at(&token, &holder, 11), Some(U256::from(100)));
+        assert_eq!(ledger.balance_at(&token, &holder, 12), Some(U256::from(60)));
+        assert!(ledger.apply((12, 5, 0), &transfer(5, 6, 1)).is_err());
+        assert!(ledger.snapshot(11).is_err());
+
+        assert_eq!(ledger.rollback(13), 2);
+        assert_eq!((balance(&ledger, 5), balance(&ledger, 6), balance(&ledger, 7)), (U256::from(60), U256::from(40), U256::zero()));
+        assert_eq!(ledger.position(), Some((11, 0, 0)));
+
+        assert_eq!(ledger.rollback(11), 1);
+        assert_eq!(balance(&ledger, 5), U256::from(100));
+        assert_eq!(ledger.balance_at(&token, &holder, 12), Some(U256::from(100)));
+        ledger.apply((11, 0, 0), &transfer(5, 8, 1)).unwrap();
+
+        ledger.prune(12);
+        assert_eq!(ledger.rollback(0), 0);
+        assert_eq!(balance(&ledger, 8), U256::from(1));
+        assert_eq!(ledger.position(), Some((11, 0, 0)));
+        assert!(ledger.apply((11, 0, 0), &transfer(5, 8, 1)).is_err());
+        assert!(ledger.apply((10, 3, 0), &transfer(5, 8, 1)).is_err());
+        ledger.apply((11, 0, 1), &transfer(5, 8, 1)).unwrap();
+    }
+
+    #[test]
+    fn applies_logs_at_their_position() {
+        let address = |a: u64| H256::from(H160::from(a));
+        let mut log = Log {
+            address: H160::from(1),
+            topics: vec![*ERC20_TRANSFER_TOPIC, address(0), address(5)],
+            data: format!("0x{:064x}", 42),
+            block_number: Some(U256::from(10)),
+            transaction_index: Some(U256::from(3)),
+            log_index: Some(U256::from(1)),
+            ..Default::default()
+        };
+
+        let mut ledger = ERC20Ledger::new();
+        ledger.apply_log(&log).unwrap();
+        assert_eq!(balance(&ledger, 5), U256::from(42));
+        assert_eq!(ledger.position(), Some((10, 3, 1)));
+
+        log.log_type = "removed".to_string();
+        log.log_index = Some(U256::from(2));
+        assert!(ledger.apply_log(&log).is_err());
+
+        log.log_type = "mined".to_string();
+        log.block_number = None;
+        assert!(ledger.apply_log(&log).is_err());
+    }
+}
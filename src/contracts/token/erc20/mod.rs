@@ -0,0 +1,8 @@
+pub mod constants;
+pub mod decoder;
+pub mod encoder;
+mod ledger;
+mod models;
+
+pub use self::ledger::{ERC20Ledger, LogPosition};
+pub use self::models::{ERC20Action, ERC20Approval, ERC20Event, ERC20Transfer};
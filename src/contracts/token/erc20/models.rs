@@ -0,0 +1,86 @@
+use contracts::{ContractFunction, NamedFunction};
+use types::{H160, U256};
+
+type Amount = U256;
+type Owner = H160;
+type Spender = H160;
+type Recipient = H160;
+
+/// A token transfer.  Mints are transfers from `0x0`; burns are usually, but not always,
+/// transfers to `0x0`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ERC20Transfer {
+    /// The token contract that emitted the event.
+    pub token: H160,
+    pub from: H160,
+    pub to: H160,
+    /// Raw amount, in the token's smallest unit.
+    pub value: U256
+}
+
+/// An allowance set by `owner` for `spender`.  The value replaces any previous allowance.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ERC20Approval {
+    /// The token contract that emitted the event.
+    pub token: H160,
+    pub owner: H160,
+    pub spender: H160,
+    /// Raw amount, in the token's smallest unit.
+    pub value: U256
+}
+
+/// An event emitted by an ERC20 token contract.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ERC20Event {
+    Transfer(ERC20Transfer),
+    Approval(ERC20Approval)
+}
+
+/// A call to an ERC20 token contract, either changing balances or allowances, or reading them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ERC20Action {
+    /// A transfer from the sender to the recipient.
+    Transfer(Recipient, Amount),
+    /// A transfer on behalf of the owner, using the sender's allowance.
+    TransferFrom(Owner, Recipient, Amount),
+    /// Sets the spender's allowance.
+    Approve(Spender, Amount),
+    /// Raises the spender's allowance by the amount.
+    IncreaseAllowance(Spender, Amount),
+    /// Lowers the spender's allowance by the amount.
+    DecreaseAllowance(Spender, Amount),
+    /// Constant call returning the owner's balance.
+    BalanceOf(Owner),
+    /// Constant call returning the amount the spender may still transfer from the owner.
+    Allowance(Owner, Spender),
+    /// Constant call returning the number of tokens in existence.
+    TotalSupply,
+    /// Constant call returning the number of decimals used to display amounts.
+    Decimals,
+    /// Constant call returning the token's symbol.
+    Symbol,
+    /// Constant call returning the token's name.
+    Name
+}
+
+impl NamedFunction for ERC20Action {
+    fn get_function(&self) -> ContractFunction {
+        match *self {
+            ERC20Action::Transfer(_, _) => ContractFunction::Mutable("ERC20.transfer".to_string()),
+            ERC20Action::TransferFrom(_, _, _) => ContractFunction::Mutable("ERC20.transferFrom".to_string()),
+            ERC20Action::Approve(_, _) => ContractFunction::Mutable("ERC20.approve".to_string()),
+            ERC20Action::IncreaseAllowance(_, _) => ContractFunction::Mutable(
+                "ERC20.increaseAllowance".to_string()
+            ),
+            ERC20Action::DecreaseAllowance(_, _) => ContractFunction::Mutable(
+                "ERC20.decreaseAllowance".to_string()
+            ),
+            ERC20Action::BalanceOf(_) => ContractFunction::Immutable("ERC20.balanceOf".to_string()),
+            ERC20Action::Allowance(_, _) => ContractFunction::Immutable("ERC20.allowance".to_string()),
+            ERC20Action::TotalSupply => ContractFunction::Immutable("ERC20.totalSupply".to_string()),
+            ERC20Action::Decimals => ContractFunction::Immutable("ERC20.decimals".to_string()),
+            ERC20Action::Symbol => ContractFunction::Immutable("ERC20.symbol".to_string()),
+            ERC20Action::Name => ContractFunction::Immutable("ERC20.name".to_string())
+        }
+    }
+}
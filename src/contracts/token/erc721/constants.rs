@@ -0,0 +1,80 @@
+use contracts::abi::Contract;
+use types::H256;
+
+lazy_static! {
+    /// The events and functions of the ERC721 standard.
+    pub static ref ERC721_ABI: Contract = Contract::from_signatures(vec![
+        "event Transfer(address indexed from, address indexed to, uint256 indexed tokenId)",
+        "event Approval(address indexed owner, address indexed approved, uint256 indexed tokenId)",
+        "event ApprovalForAll(address indexed owner, address indexed operator, bool approved)",
+        "function safeTransferFrom(address from, address to, uint256 tokenId)",
+        "function safeTransferFrom(address from, address to, uint256 tokenId, bytes data)",
+        "function transferFrom(address from, address to, uint256 tokenId)",
+        "function approve(address approved, uint256 tokenId)",
+        "function setApprovalForAll(address operator, bool approved)",
+        "function ownerOf(uint256 tokenId) view returns (address)",
+        "function tokenURI(uint256 tokenId) view returns (string)"
+    ]).expect("Invalid ERC721 ABI");
+
+    /// Shared with ERC20's `Transfer`, which doesn't index its third parameter.
+    pub static ref ERC721_TRANSFER_TOPIC: H256 = event_topic("Transfer");
+    /// Shared with ERC20's `Approval`, which doesn't index its third parameter.
+    pub static ref ERC721_APPROVAL_TOPIC: H256 = event_topic("Approval");
+    pub static ref ERC721_APPROVAL_FOR_ALL_TOPIC: H256 = event_topic("ApprovalForAll");
+
+    // Methods.  `transferFrom` and `approve` are encoded exactly as ERC20's.
+    pub static ref SAFE_TRANSFER_FROM_ID: String = method_id("safeTransferFrom(address,address,uint256)");
+    pub static ref SAFE_TRANSFER_FROM_WITH_DATA_ID: String = {
+        method_id("safeTransferFrom(address,address,uint256,bytes)")
+    };
+    pub static ref TRANSFER_FROM_ID: String = method_id("transferFrom(address,address,uint256)");
+    pub static ref APPROVE_ID: String = method_id("approve(address,uint256)");
+    pub static ref SET_APPROVAL_FOR_ALL_ID: String = method_id("setApprovalForAll(address,bool)");
+
+    // Static methods
+    pub static ref OWNER_OF_ID: String = method_id("ownerOf(uint256)");
+    pub static ref TOKEN_URI_ID: String = method_id("tokenURI(uint256)");
+}
+
+fn method_id(signature: &str) -> String {
+    ERC721_ABI.functions.iter()
+        .find(|f| f.signature() == signature)
+        .map(|f| f.method_id())
+        .unwrap_or_else(|| panic!("ERC721 ABI is missing function {}", signature))
+}
+
+fn event_topic(name: &str) -> H256 {
+    ERC721_ABI.event(name)
+        .map(|e| e.topic())
+        .unwrap_or_else(|| panic!("ERC721 ABI is missing event {}", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use contracts::token::erc20::constants::{ERC20_APPROVAL_TOPIC, ERC20_TRANSFER_TOPIC};
+    use types::H256;
+    use super::*;
+
+    #[test]
+    fn topics_match_standard() {
+        assert_eq!(*ERC721_TRANSFER_TOPIC, *ERC20_TRANSFER_TOPIC);
+        assert_eq!(*ERC721_APPROVAL_TOPIC, *ERC20_APPROVAL_TOPIC);
+        assert_eq!(
+            *ERC721_APPROVAL_FOR_ALL_TOPIC,
+            H256::from_str("17307eab39ab6107e8899845ad3d59bd9653f200f220920489ca2b5937696c31").unwrap()
+        );
+    }
+
+    #[test]
+    fn method_ids_match_standard() {
+        assert_eq!(*SAFE_TRANSFER_FROM_ID, "0x42842e0e");
+        assert_eq!(*SAFE_TRANSFER_FROM_WITH_DATA_ID, "0xb88d4fde");
+        assert_eq!(*TRANSFER_FROM_ID, "0x23b872dd");
+        assert_eq!(*APPROVE_ID, "0x095ea7b3");
+        assert_eq!(*SET_APPROVAL_FOR_ALL_ID, "0xa22cb465");
+        assert_eq!(*OWNER_OF_ID, "0x6352211e");
+        assert_eq!(*TOKEN_URI_ID, "0xc87b56dd");
+    }
+}
@@ -0,0 +1,222 @@
+use std::vec;
+
+use contracts::abi::{take_value, AbiValue, DecodeMode};
+use error::{Error, ErrorKind};
+use objects::{Log, ParityTrace, Transaction};
+
+use super::constants::*;
+use super::models::*;
+
+/// Decoder for the calls to and events of any ERC721 token contract.
+///
+/// `Transfer` and `Approval` share their topics with ERC20, and are only decoded when the
+/// token id is indexed.  `transferFrom` and `approve` calls are encoded exactly as ERC20's, so
+/// the kind of contract called must be known beforehand.
+pub struct ERC721Decoder;
+
+impl ERC721Decoder {
+    pub fn decode_action(tx: &Transaction) -> Result<ERC721Action, Error> {
+        ERC721Decoder::decode_input(&tx.input)
+    }
+
+    pub fn decode_trace_action(trace: &ParityTrace) -> Result<ERC721Action, Error> {
+        ERC721Decoder::decode_input(&trace.action.input)
+    }
+
+    pub fn decode_input(input: &str) -> Result<ERC721Action, Error> {
+        let method_id = match input.get(..10) {
+            Some(id) => id.to_lowercase(),
+            None => return Err(decode_error(format!("Input is too short for an ERC721 call: {}", input)))
+        };
+
+        match method_id.as_str() {
+            id if id == *SAFE_TRANSFER_FROM_ID => {
+                let mut args = call_args(input)?;
+                Ok(ERC721Action::SafeTransferFrom(
+                    take_value(&mut args)?, take_value(&mut args)?, take_value(&mut args)?, None
+                ))
+            },
+            id if id == *SAFE_TRANSFER_FROM_WITH_DATA_ID => {
+                let mut args = call_args(input)?;
+                Ok(ERC721Action::SafeTransferFrom(
+                    take_value(&mut args)?, take_value(&mut args)?, take_value(&mut args)?, Some(take_value(&mut args)?)
+                ))
+            },
+            id if id == *TRANSFER_FROM_ID => {
+                let mut args = call_args(input)?;
+                Ok(ERC721Action::TransferFrom(take_value(&mut args)?, take_value(&mut args)?, take_value(&mut args)?))
+            },
+            id if id == *APPROVE_ID => {
+                let mut args = call_args(input)?;
+                Ok(ERC721Action::Approve(take_value(&mut args)?, take_value(&mut args)?))
+            },
+            id if id == *SET_APPROVAL_FOR_ALL_ID => {
+                let mut args = call_args(input)?;
+                Ok(ERC721Action::SetApprovalForAll(take_value(&mut args)?, take_value(&mut args)?))
+            },
+            id if id == *OWNER_OF_ID => {
+                let mut args = call_args(input)?;
+                Ok(ERC721Action::OwnerOf(take_value(&mut args)?))
+            },
+            id if id == *TOKEN_URI_ID => {
+                let mut args = call_args(input)?;
+                Ok(ERC721Action::TokenURI(take_value(&mut args)?))
+            },
+            _ => Err(decode_error(format!("Not an ERC721 method: {}", method_id)))
+        }
+    }
+
+    pub fn decode_event(log: &Log) -> Result<ERC721Event, Error> {
+        match log.topics.first() {
+            Some(t) if *t == *ERC721_TRANSFER_TOPIC => {
+                ERC721Decoder::decode_transfer(log).map(ERC721Event::Transfer)
+            },
+            Some(t) if *t == *ERC721_APPROVAL_TOPIC => {
+                ERC721Decoder::decode_approval(log).map(ERC721Event::Approval)
+            },
+            Some(t) if *t == *ERC721_APPROVAL_FOR_ALL_TOPIC => {
+                ERC721Decoder::decode_approval_for_all(log).map(ERC721Event::ApprovalForAll)
+            },
+            Some(t) => Err(decode_error(format!("Not an ERC721 event: {:?}", t))),
+            None => Err(decode_error("Expected log topic vec of length (1)".to_string()))
+        }
+    }
+
+    /// Fails for ERC20 transfers, which have one topic less.
+    pub fn decode_transfer(log: &Log) -> Result<ERC721Transfer, Error> {
+        let fields = ERC721_ABI.decode_log("Transfer", log)?;
+
+        Ok(ERC721Transfer {
+            token: log.address,
+            from: fields.address("from")?,
+            to: fields.address("to")?,
+            token_id: fields.uint("tokenId")?
+        })
+    }
+
+    /// Fails for ERC20 approvals, which have one topic less.
+    pub fn decode_approval(log: &Log) -> Result<ERC721Approval, Error> {
+        let fields = ERC721_ABI.decode_log("Approval", log)?;
+
+        Ok(ERC721Approval {
+            token: log.address,
+            owner: fields.address("owner")?,
+            approved: fields.address("approved")?,
+            token_id: fields.uint("tokenId")?
+        })
+    }
+
+    pub fn decode_approval_for_all(log: &Log) -> Result<ERC721ApprovalForAll, Error> {
+        let fields = ERC721_ABI.decode_log("ApprovalForAll", log)?;
+
+        Ok(ERC721ApprovalForAll {
+            token: log.address,
+            owner: fields.address("owner")?,
+            operator: fields.address("operator")?,
+            approved: fields.boolean("approved")?
+        })
+    }
+}
+
+fn call_args(input: &str) -> Result<vec::IntoIter<AbiValue>, Error> {
+    let function = ERC721_ABI.function_by_method_id(input)
+        .ok_or_else(|| decode_error(format!("Not an ERC721 method: {}", input)))?;
+    Ok(function.decode_input(input, DecodeMode::Strict)?.into_iter())
+}
+
+fn decode_error(message: String) -> Error {
+    ErrorKind::Decoder(message).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use contracts::{ContractFunction, NamedFunction};
+    use contracts::abi::AbiValue;
+    use objects::fixtures::log;
+    use types::{H160, H256, U256};
+    use super::ERC721Decoder;
+    use super::super::constants::*;
+    use super::super::models::*;
+
+    const KITTIES: &str = "06012c8cf97bead5deae237070f9587f8e7a266d";
+    const OWNER: &str = "461680c41786ec889ac9de7f5e89a2f4e0fcb156";
+    const OPERATOR: &str = "8d12a197cb00d4747a1fe03395095ce2a5cc6819";
+
+    fn word(hex: &str) -> H256 {
+        H256::from_str(&format!("{:0>64}", hex)).unwrap()
+    }
+
+    #[test]
+    fn decodes_events() {
+        let kitties = H160::from_str(KITTIES).unwrap();
+        let transfer = log(kitties, vec![*ERC721_TRANSFER_TOPIC, word(OWNER), word(OPERATOR), word("2a")], "0x");
+        assert_eq!(
+            ERC721Decoder::decode_event(&transfer).unwrap(),
+            ERC721Event::Transfer(ERC721Transfer {
+                token: kitties,
+                from: H160::from_str(OWNER).unwrap(),
+                to: H160::from_str(OPERATOR).unwrap(),
+                token_id: U256::from(42)
+            })
+        );
+
+        let approval_for_all = log(
+            kitties,
+            vec![*ERC721_APPROVAL_FOR_ALL_TOPIC, word(OWNER), word(OPERATOR)],
+            &format!("0x{:064x}", 1)
+        );
+        match ERC721Decoder::decode_event(&approval_for_all).unwrap() {
+            ERC721Event::ApprovalForAll(approval) => {
+                assert_eq!(approval.operator, H160::from_str(OPERATOR).unwrap());
+                assert!(approval.approved);
+            },
+            other => panic!("Expected an approval for all, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn rejects_erc20_logs() {
+        let kitties = H160::from_str(KITTIES).unwrap();
+        let value = format!("0x{:064x}", 42);
+        let erc20 = log(kitties, vec![*ERC721_TRANSFER_TOPIC, word(OWNER), word(OPERATOR)], &value);
+        assert!(ERC721Decoder::decode_event(&erc20).is_err());
+
+        let erc20 = log(kitties, vec![*ERC721_APPROVAL_TOPIC, word(OWNER), word(OPERATOR)], &value);
+        assert!(ERC721Decoder::decode_event(&erc20).is_err());
+    }
+
+    #[test]
+    fn decodes_calls() {
+        let (owner, to) = (H160::from_str(OWNER).unwrap(), H160::from_str(OPERATOR).unwrap());
+        let function = |signature: &str| ERC721_ABI.functions.iter().find(|f| f.signature() == signature).unwrap();
+
+        let input = function("safeTransferFrom(address,address,uint256,bytes)").encode_input_hex(&[
+            AbiValue::Address(owner), AbiValue::Address(to), AbiValue::Uint(U256::from(7)), AbiValue::Bytes(vec![1, 2])
+        ]).unwrap();
+        assert_eq!(
+            ERC721Decoder::decode_input(&input).unwrap(),
+            ERC721Action::SafeTransferFrom(owner, to, U256::from(7), Some(vec![1, 2]))
+        );
+
+        let input = format!("{}{:0>64}{:0>64}{:064x}", *SAFE_TRANSFER_FROM_ID, OWNER, OPERATOR, 7);
+        assert_eq!(
+            ERC721Decoder::decode_input(&input).unwrap(),
+            ERC721Action::SafeTransferFrom(owner, to, U256::from(7), None)
+        );
+
+        let input = format!("{}{:0>64}{:064x}", *SET_APPROVAL_FOR_ALL_ID, OPERATOR, 1);
+        assert_eq!(ERC721Decoder::decode_input(&input).unwrap(), ERC721Action::SetApprovalForAll(to, true));
+
+        let token_uri = ERC721Decoder::decode_input(&format!("{}{:064x}", *TOKEN_URI_ID, 7)).unwrap();
+        assert_eq!(token_uri, ERC721Action::TokenURI(U256::from(7)));
+        match token_uri.get_function() {
+            ContractFunction::Immutable(name) => assert_eq!(name, "ERC721.tokenURI"),
+            _ => panic!("tokenURI is constant")
+        }
+
+        assert!(ERC721Decoder::decode_input("0xdeadbeef").is_err());
+        assert!(ERC721Decoder::decode_input(&format!("{}{:0>64}", *SET_APPROVAL_FOR_ALL_ID, OPERATOR)).is_err());
+    }
+}
@@ -0,0 +1,7 @@
+pub mod constants;
+pub mod decoder;
+mod models;
+mod ownership;
+
+pub use self::models::{ERC721Action, ERC721Approval, ERC721ApprovalForAll, ERC721Event, ERC721Transfer};
+pub use self::ownership::OwnershipTracker;
@@ -0,0 +1,80 @@
+use contracts::{ContractFunction, NamedFunction};
+use types::{H160, U256};
+
+type TokenId = U256;
+type Owner = H160;
+type Operator = H160;
+type Recipient = H160;
+
+/// A change of a token's owner.  Mints are transfers from `0x0`, and burns transfers to `0x0`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ERC721Transfer {
+    /// The token contract that emitted the event.
+    pub token: H160,
+    pub from: H160,
+    pub to: H160,
+    pub token_id: U256
+}
+
+/// The address approved to transfer a single token.  `0x0` clears the approval.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ERC721Approval {
+    /// The token contract that emitted the event.
+    pub token: H160,
+    pub owner: H160,
+    pub approved: H160,
+    pub token_id: U256
+}
+
+/// An operator allowed, or no longer allowed, to transfer all of the owner's tokens.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ERC721ApprovalForAll {
+    /// The token contract that emitted the event.
+    pub token: H160,
+    pub owner: H160,
+    pub operator: H160,
+    pub approved: bool
+}
+
+/// An event emitted by an ERC721 token contract.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ERC721Event {
+    Transfer(ERC721Transfer),
+    Approval(ERC721Approval),
+    ApprovalForAll(ERC721ApprovalForAll)
+}
+
+/// A call to an ERC721 token contract.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ERC721Action {
+    /// A transfer that checks that a contract recipient accepts the token.  The data is `None`
+    /// for the overload without a `bytes` argument.
+    SafeTransferFrom(Owner, Recipient, TokenId, Option<Vec<u8>>),
+    /// A transfer without the recipient check.
+    TransferFrom(Owner, Recipient, TokenId),
+    /// Approves an address to transfer the token.
+    Approve(H160, TokenId),
+    /// Allows, or disallows, an operator to transfer all of the sender's tokens.
+    SetApprovalForAll(Operator, bool),
+    /// Constant call returning the token's owner.
+    OwnerOf(TokenId),
+    /// Constant call returning the token's metadata URI.
+    TokenURI(TokenId)
+}
+
+impl NamedFunction for ERC721Action {
+    fn get_function(&self) -> ContractFunction {
+        match *self {
+            ERC721Action::SafeTransferFrom(_, _, _, _) => ContractFunction::Mutable(
+                "ERC721.safeTransferFrom".to_string()
+            ),
+            ERC721Action::TransferFrom(_, _, _) => ContractFunction::Mutable("ERC721.transferFrom".to_string()),
+            ERC721Action::Approve(_, _) => ContractFunction::Mutable("ERC721.approve".to_string()),
+            ERC721Action::SetApprovalForAll(_, _) => ContractFunction::Mutable(
+                "ERC721.setApprovalForAll".to_string()
+            ),
+            ERC721Action::OwnerOf(_) => ContractFunction::Immutable("ERC721.ownerOf".to_string()),
+            ERC721Action::TokenURI(_) => ContractFunction::Immutable("ERC721.tokenURI".to_string())
+        }
+    }
+}
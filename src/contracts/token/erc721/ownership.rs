@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+
+use error::Error;
+use objects::Log;
+use types::{H160, U256};
+
+use super::decoder::ERC721Decoder;
+use super::models::ERC721Transfer;
+
+/// The current owner of every token seen in a replay of `Transfer` events, keyed by token
+/// contract and token id.  Transfers must be applied in the order they were emitted.
+#[derive(Debug, Clone, Default)]
+pub struct OwnershipTracker {
+    owners: HashMap<(H160, U256), H160>
+}
+
+impl OwnershipTracker {
+    pub fn new() -> OwnershipTracker {
+        OwnershipTracker::default()
+    }
+
+    /// Moves the token to its recipient, or forgets it if burned (sent to `0x0`).  Returns
+    /// the previous owner, if the token was known.
+    pub fn apply(&mut self, transfer: &ERC721Transfer) -> Option<H160> {
+        let key = (transfer.token, transfer.token_id);
+        if transfer.to.is_zero() {
+            self.owners.remove(&key)
+        } else {
+            self.owners.insert(key, transfer.to)
+        }
+    }
+
+    /// Decodes and applies an ERC721 `Transfer` log.
+    pub fn apply_log(&mut self, log: &Log) -> Result<Option<H160>, Error> {
+        ERC721Decoder::decode_transfer(log).map(|transfer| self.apply(&transfer))
+    }
+
+    pub fn owner_of(&self, token: &H160, token_id: &U256) -> Option<H160> {
+        self.owners.get(&(*token, *token_id)).cloned()
+    }
+
+    /// The ids of the tokens of a contract held by `owner`, in ascending order.
+    pub fn tokens_of(&self, token: &H160, owner: &H160) -> Vec<U256> {
+        let mut ids = self.owners.iter()
+            .filter(|&(&(t, _), o)| t == *token && o == owner)
+            .map(|(&(_, id), _)| id)
+            .collect::<Vec<_>>();
+        ids.sort();
+        ids
+    }
+
+    /// Number of tokens with a known owner.
+    pub fn len(&self) -> usize {
+        self.owners.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.owners.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use types::{H160, U256};
+    use super::OwnershipTracker;
+    use super::super::models::ERC721Transfer;
+
+    fn transfer(from: u64, to: u64, token_id: u64) -> ERC721Transfer {
+        ERC721Transfer {
+            token: H160::from(1),
+            from: H160::from(from),
+            to: H160::from(to),
+            token_id: U256::from(token_id)
+        }
+    }
+
+    #[test]
+    fn replays_transfers() {
+        let mut tracker = OwnershipTracker::new();
+        let token = H160::from(1);
+
+        assert_eq!(tracker.apply(&transfer(0, 10, 1)), None);
+        assert_eq!(tracker.apply(&transfer(0, 10, 2)), None);
+        assert_eq!(tracker.apply(&transfer(10, 20, 1)), Some(H160::from(10)));
+
+        assert_eq!(tracker.owner_of(&token, &U256::from(1)), Some(H160::from(20)));
+        assert_eq!(tracker.tokens_of(&token, &H160::from(10)), vec![U256::from(2)]);
+        assert_eq!(tracker.owner_of(&H160::from(2), &U256::from(1)), None);
+
+        assert_eq!(tracker.apply(&transfer(10, 0, 2)), Some(H160::from(10)));
+        assert_eq!(tracker.owner_of(&token, &U256::from(2)), None);
+        assert_eq!(tracker.len(), 1);
+    }
+}
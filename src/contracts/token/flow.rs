@@ -0,0 +1,300 @@
+use std::collections::{HashMap, HashSet};
+
+use error::{Error, ErrorKind};
+use objects::trace::CallType;
+use objects::{Log, ParityTrace};
+use types::{H160, U256};
+
+use super::erc1155::constants::{ERC1155_TRANSFER_BATCH_TOPIC, ERC1155_TRANSFER_SINGLE_TOPIC};
+use super::erc1155::decoder::ERC1155Decoder;
+use super::erc1155::ERC1155Event;
+use super::erc20::constants::ERC20_TRANSFER_TOPIC;
+use super::erc20::decoder::ERC20Decoder;
+use super::erc721::decoder::ERC721Decoder;
+use super::weth::constants::{WETH_ADDRESS, WETH_DEPOSIT_TOPIC, WETH_WITHDRAWAL_TOPIC};
+use super::weth::decoder::WETHDecoder;
+
+/// Something whose balance can change hands: ether, an ERC20 token, or a token id of an
+/// ERC721 or ERC1155 contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Asset {
+    Ether,
+    ERC20(H160),
+    ERC721(H160, U256),
+    ERC1155(H160, U256)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NetChange {
+    Gain(U256),
+    Loss(U256)
+}
+
+/// What each address gained and lost in a transaction.
+///
+/// Ether moves with the value of the successful frames of the transaction's traces, and tokens
+/// with its ERC20, ERC721 and ERC1155 transfer logs.  WETH deposits and withdrawals are mints
+/// and burns of WETH, so wrapping ether shows as ether sent to the WETH contract and WETH
+/// received.  Any contract can emit logs with the same topics, so only those of WETH, or of
+/// the wrappers given to `with_wrappers`, count.  Mints and burns don't count as flows of
+/// `0x0`.
+#[derive(Debug, Clone)]
+pub struct AssetFlow {
+    /// Amounts received and sent by address and asset.
+    flows: HashMap<(H160, Asset), (U256, U256)>,
+    /// Contracts whose `Deposit` and `Withdrawal` logs mint and burn wrapped ether.
+    wrappers: HashSet<H160>
+}
+
+impl Default for AssetFlow {
+    fn default() -> AssetFlow {
+        AssetFlow::with_wrappers(vec![*WETH_ADDRESS])
+    }
+}
+
+impl AssetFlow {
+    pub fn new() -> AssetFlow {
+        AssetFlow::default()
+    }
+
+    /// Flows counting the deposits and withdrawals of `wrappers` instead of WETH's, e.g. for
+    /// the wrapped ether of another chain.
+    pub fn with_wrappers<I: IntoIterator<Item = H160>>(wrappers: I) -> AssetFlow {
+        AssetFlow { flows: HashMap::new(), wrappers: wrappers.into_iter().collect() }
+    }
+
+    /// The flows of a transaction, from its traces and the logs of its receipt.
+    pub fn from_transaction(traces: &[ParityTrace], logs: &[Log]) -> Result<AssetFlow, Error> {
+        let mut flow = AssetFlow::new();
+        flow.add_traces(traces)?;
+        flow.add_logs(logs)?;
+        Ok(flow)
+    }
+
+    /// Adds the ether moved by calls, contract creations and self-destructs.  Frames that
+    /// failed, and the frames they called, moved nothing.  Delegate calls reuse the value of
+    /// their caller, so they're skipped too.
+    pub fn add_traces(&mut self, traces: &[ParityTrace]) -> Result<(), Error> {
+        let failed = traces.iter()
+            .filter(|trace| trace.is_failed())
+            .map(|trace| &trace.trace_address[..])
+            .collect::<HashSet<_>>();
+
+        for trace in traces {
+            let reverted = (0..=trace.trace_address.len())
+                .any(|depth| failed.contains(&trace.trace_address[..depth]));
+            if reverted {
+                continue;
+            }
+
+            let action = &trace.action;
+            let transfer = match trace.action_type {
+                CallType::Suicide => (action.address, action.refund_address, action.balance),
+                CallType::Create => (action.from, trace.result.as_ref().and_then(|r| r.address), action.value),
+                _ => match action.call_type {
+                    Some(CallType::DelegateCall) | Some(CallType::StaticCall) => continue,
+                    _ => (action.from, action.to, action.value)
+                }
+            };
+
+            if let (Some(from), Some(to), Some(value)) = transfer {
+                self.add(from, to, Asset::Ether, value)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds the transfers, deposits and withdrawals in the logs.  Other logs, logs with a
+    /// transfer topic that don't decode, and deposits and withdrawals of contracts that aren't
+    /// wrappers are skipped: any contract can emit them.
+    pub fn add_logs(&mut self, logs: &[Log]) -> Result<(), Error> {
+        for log in logs.iter().filter(|log| log.log_type != "removed") {
+            let topic = match log.topics.first() {
+                Some(topic) => *topic,
+                None => continue
+            };
+
+            if topic == *ERC20_TRANSFER_TOPIC && log.topics.len() == 4 {
+                if let Ok(t) = ERC721Decoder::decode_transfer(log) {
+                    self.add(t.from, t.to, Asset::ERC721(t.token, t.token_id), U256::one())?;
+                }
+            } else if topic == *ERC20_TRANSFER_TOPIC {
+                if let Ok(t) = ERC20Decoder::decode_transfer(log) {
+                    self.add(t.from, t.to, Asset::ERC20(t.token), t.value)?;
+                }
+            } else if topic == *ERC1155_TRANSFER_SINGLE_TOPIC || topic == *ERC1155_TRANSFER_BATCH_TOPIC {
+                let transfers = match ERC1155Decoder::decode_event(log) {
+                    Ok(ERC1155Event::TransferSingle(t)) => vec![t],
+                    Ok(ERC1155Event::TransferBatch(batch)) => batch.transfers(),
+                    _ => continue
+                };
+                for t in transfers {
+                    self.add(t.from, t.to, Asset::ERC1155(t.token, t.id), t.value)?;
+                }
+            } else if topic == *WETH_DEPOSIT_TOPIC || topic == *WETH_WITHDRAWAL_TOPIC {
+                if !self.wrappers.contains(&log.address) {
+                    continue;
+                }
+                if let Ok(event) = WETHDecoder::decode_event(log) {
+                    let t = event.to_transfer();
+                    self.add(t.from, t.to, Asset::ERC20(t.token), t.value)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Records `value` of the asset moving from one address to another.
+    pub fn add(&mut self, from: H160, to: H160, asset: Asset, value: U256) -> Result<(), Error> {
+        if value.is_zero() || from == to {
+            return Ok(());
+        }
+        if !from.is_zero() {
+            let sent = &mut self.flows.entry((from, asset)).or_default().1;
+            *sent = sent.checked_add(value).ok_or_else(|| overflow(&from, &asset))?;
+        }
+        if !to.is_zero() {
+            let received = &mut self.flows.entry((to, asset)).or_default().0;
+            *received = received.checked_add(value).ok_or_else(|| overflow(&to, &asset))?;
+        }
+        Ok(())
+    }
+
+    /// The net change of an address's balance of the asset, or `None` if it didn't change.
+    pub fn net(&self, address: &H160, asset: &Asset) -> Option<NetChange> {
+        self.flows.get(&(*address, *asset)).and_then(|&(received, sent)| {
+            if received > sent {
+                Some(NetChange::Gain(received - sent))
+            } else if sent > received {
+                Some(NetChange::Loss(sent - received))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// The net changes of an address, by asset.
+    pub fn changes_of(&self, address: &H160) -> Vec<(Asset, NetChange)> {
+        self.changes().into_iter()
+            .filter(|&(a, _, _)| a == *address)
+            .map(|(_, asset, change)| (asset, change))
+            .collect()
+    }
+
+    /// Every net change, by address and asset.
+    pub fn changes(&self) -> Vec<(H160, Asset, NetChange)> {
+        let mut changes = self.flows.keys()
+            .filter_map(|&(address, asset)| self.net(&address, &asset).map(|change| (address, asset, change)))
+            .collect::<Vec<_>>();
+        changes.sort_by_key(|&(address, asset, _)| (address, asset));
+        changes
+    }
+}
+
+fn overflow(address: &H160, asset: &Asset) -> Error {
+    ErrorKind::Amount(format!("Flow of {:?} for {:?} overflows", asset, address)).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use serde_json;
+
+    use objects::fixtures::log;
+    use objects::{Log, ParityTrace};
+    use types::{H160, H256, U256};
+    use super::{Asset, AssetFlow, NetChange};
+    use super::super::erc1155::constants::ERC1155_TRANSFER_SINGLE_TOPIC;
+    use super::super::erc20::constants::{ERC20_APPROVAL_TOPIC, ERC20_TRANSFER_TOPIC};
+    use super::super::weth::constants::{WETH_ADDRESS, WETH_DEPOSIT_TOPIC, WETH_WITHDRAWAL_TOPIC};
+
+    const USER: &str = "461680c41786ec889ac9de7f5e89a2f4e0fcb156";
+    const PROXY: &str = "f43b43869dd95e53923b30a79902915c1faa47d3";
+    const MAKER: &str = "000029c8bf6f0790f2bbc8afee6726e2b554a58e";
+    const ZRX: &str = "e41d2489571d322189246dafa5ebde1f4699f498";
+    const KILLED: &str = "8d12a197cb00d4747a1fe03395095ce2a5cc6819";
+
+    fn address(a: &str) -> H160 {
+        H160::from_str(a).unwrap()
+    }
+
+    fn topic(a: &str) -> H256 {
+        H256::from(address(a))
+    }
+
+    fn logs() -> Vec<Log> {
+        let ether = U256::from_dec_str("1000000000000000000").unwrap();
+        let wad = format!("0x{:0>64}", format!("{:x}", ether));
+        let (zrx, nft, multi) = (address(ZRX), H160::from(721), H160::from(1155));
+        vec![
+            log(*WETH_ADDRESS, vec![*WETH_DEPOSIT_TOPIC, topic(PROXY)], &wad),
+            log(*WETH_ADDRESS, vec![*ERC20_TRANSFER_TOPIC, topic(PROXY), topic(MAKER)], &wad),
+            log(zrx, vec![*ERC20_TRANSFER_TOPIC, topic(MAKER), topic(PROXY)], &format!("0x{:064x}", 500)),
+            log(zrx, vec![*ERC20_TRANSFER_TOPIC, topic(PROXY), topic(USER)], &format!("0x{:064x}", 500)),
+            log(zrx, vec![*ERC20_APPROVAL_TOPIC, topic(USER), topic(PROXY)], &format!("0x{:064x}", 9)),
+            log(nft, vec![*ERC20_TRANSFER_TOPIC, topic(MAKER), topic(USER), H256::from(7)], "0x"),
+            log(
+                multi,
+                vec![*ERC1155_TRANSFER_SINGLE_TOPIC, topic(MAKER), topic(MAKER), topic(USER)],
+                &format!("0x{:064x}{:064x}", 2, 10)
+            ),
+            log(zrx, vec![*ERC20_TRANSFER_TOPIC, topic(MAKER)], "0x")
+        ]
+    }
+
+    #[test]
+    fn nets_ether_and_token_flows() {
+        let traces = serde_json::from_str::<Vec<ParityTrace>>(
+            include_str!("../../../test_data/asset_flow_trace.json")
+        ).unwrap();
+        let flow = AssetFlow::from_transaction(&traces, &logs()).unwrap();
+        let ether = U256::from_dec_str("1000000000000000000").unwrap();
+        let (zrx, nft, multi) = (address(ZRX), H160::from(721), H160::from(1155));
+
+        let refund = U256::from_dec_str("200000000000000000").unwrap();
+
+        assert_eq!(flow.changes_of(&address(USER)), vec![
+            (Asset::Ether, NetChange::Loss(ether - refund)),
+            (Asset::ERC20(zrx), NetChange::Gain(U256::from(500))),
+            (Asset::ERC721(nft, U256::from(7)), NetChange::Gain(U256::one())),
+            (Asset::ERC1155(multi, U256::from(2)), NetChange::Gain(U256::from(10)))
+        ]);
+        assert_eq!(flow.changes_of(&address(MAKER)), vec![
+            (Asset::ERC20(*WETH_ADDRESS), NetChange::Gain(ether)),
+            (Asset::ERC20(zrx), NetChange::Loss(U256::from(500))),
+            (Asset::ERC721(nft, U256::from(7)), NetChange::Loss(U256::one())),
+            (Asset::ERC1155(multi, U256::from(2)), NetChange::Loss(U256::from(10)))
+        ]);
+        assert_eq!(flow.net(&WETH_ADDRESS, &Asset::Ether), Some(NetChange::Gain(ether)));
+        assert_eq!(flow.net(&address(KILLED), &Asset::Ether), Some(NetChange::Loss(refund)));
+        assert!(flow.changes_of(&address(PROXY)).is_empty());
+        assert!(flow.changes_of(&H160::zero()).is_empty());
+        assert_eq!(flow.changes().len(), 10);
+    }
+
+    #[test]
+    fn only_counts_deposits_of_wrappers() {
+        let fake = H160::from(9);
+        let deposit = log(fake, vec![*WETH_DEPOSIT_TOPIC, topic(USER)], &format!("0x{:064x}", 5));
+        let withdrawal = log(fake, vec![*WETH_WITHDRAWAL_TOPIC, topic(MAKER)], &format!("0x{:064x}", 3));
+        let logs = vec![deposit, withdrawal];
+
+        let flow = AssetFlow::from_transaction(&[], &logs).unwrap();
+        assert!(flow.changes().is_empty());
+
+        let mut flow = AssetFlow::with_wrappers(vec![fake]);
+        flow.add_logs(&logs).unwrap();
+        assert_eq!(flow.changes(), vec![
+            (address(MAKER), Asset::ERC20(fake), NetChange::Loss(U256::from(3))),
+            (address(USER), Asset::ERC20(fake), NetChange::Gain(U256::from(5)))
+        ]);
+    }
+
+    #[test]
+    fn rejects_overflowing_flows() {
+        let mut flow = AssetFlow::new();
+        flow.add(H160::from(1), H160::from(2), Asset::Ether, U256::max_value()).unwrap();
+        assert!(flow.add(H160::from(3), H160::from(2), Asset::Ether, U256::one()).is_err());
+    }
+}
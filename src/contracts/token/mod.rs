@@ -0,0 +1,98 @@
+mod amount;
+pub mod erc1155;
+pub mod erc20;
+pub mod erc721;
+mod flow;
+mod price;
+mod registry;
+pub mod weth;
+
+use types::U256;
+
+use self::erc20::encoder::ERC20Encoder;
+
+pub use self::amount::TokenAmount;
+pub use self::erc1155::{
+    BalanceTracker,
+    ERC1155Action,
+    ERC1155ApprovalForAll,
+    ERC1155Event,
+    ERC1155TransferBatch,
+    ERC1155TransferSingle,
+    ERC1155URI
+};
+pub use self::erc20::{ERC20Action, ERC20Approval, ERC20Event, ERC20Ledger, ERC20Transfer, LogPosition};
+pub use self::erc721::{
+    ERC721Action,
+    ERC721Approval,
+    ERC721ApprovalForAll,
+    ERC721Event,
+    ERC721Transfer,
+    OwnershipTracker
+};
+pub use self::flow::{Asset, AssetFlow, NetChange};
+pub use self::price::Price;
+pub use self::registry::{TokenRegistry, MAINNET};
+pub use self::weth::{WETHAction, WETHDeposit, WETHEvent, WETHWithdrawal};
+
+/// ERC20 model for contracts implementing this standard
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ERC20 {
+    /// Token name
+    pub name: String,
+    /// Token symbol
+    pub symbol: String,
+    /// Token decimals
+    pub decimals: u8,
+    /// Token total supply
+    pub total_supply: U256,
+    /// Link to the token's icon (not part of ERC20 standard.
+    /// Used by Etherswap internally.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon_url: Option<String>
+}
+
+impl ERC20 {
+    /// Builds the record from the hex outputs of `eth_call`s to `name()`, `symbol()`,
+    /// `decimals()` and `totalSupply()`, built with `ERC20Encoder`.  Pass `None` for calls that
+    /// failed or reverted.  Fields that are missing or can't be decoded are left empty: blank
+    /// strings, and zero decimals and supply, so tokens without `decimals()` count whole units.
+    pub fn from_call_outputs(
+        name: Option<&str>,
+        symbol: Option<&str>,
+        decimals: Option<&str>,
+        total_supply: Option<&str>
+    ) -> ERC20 {
+        ERC20 {
+            name: name.and_then(|o| ERC20Encoder::decode_name(o).ok()).unwrap_or_default(),
+            symbol: symbol.and_then(|o| ERC20Encoder::decode_symbol(o).ok()).unwrap_or_default(),
+            decimals: decimals.and_then(|o| ERC20Encoder::decode_decimals(o).ok()).unwrap_or(0),
+            total_supply: total_supply.and_then(|o| ERC20Encoder::decode_total_supply(o).ok()).unwrap_or_default(),
+            icon_url: None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use contracts::abi::{encode_hex, AbiValue};
+    use types::U256;
+    use super::ERC20;
+
+    #[test]
+    fn builds_partial_records() {
+        let name = "0x4d616b6572000000000000000000000000000000000000000000000000000000";
+        let symbol = encode_hex(&[AbiValue::String("MKR".to_string())]);
+        let supply = format!("0x{:064x}", 1000);
+
+        let mkr = ERC20::from_call_outputs(Some(name), Some(&symbol), Some("0x"), Some(&supply));
+        assert_eq!(mkr.name, "Maker");
+        assert_eq!(mkr.symbol, "MKR");
+        assert_eq!(mkr.decimals, 0);
+        assert_eq!(mkr.total_supply, U256::from(1000));
+
+        let unknown = ERC20::from_call_outputs(None, Some("0xzz"), None, None);
+        assert_eq!(unknown.symbol, "");
+        assert_eq!(unknown.total_supply, U256::zero());
+    }
+}
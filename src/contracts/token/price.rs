@@ -0,0 +1,178 @@
+use std::fmt;
+use std::str::FromStr;
+
+use num::bigint::BigUint;
+use num::rational::Ratio;
+use num::{pow, Integer, ToPrimitive, Zero};
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+
+use error::{Error, ErrorKind};
+use types::U256;
+
+use super::TokenAmount;
+
+/// An exact price: the amount of a quote token paid per whole unit of a base token, kept as a
+/// reduced fraction so that nothing is rounded until it's displayed.  Serialized as the
+/// fraction, like `"1/4000"`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Price(Ratio<BigUint>);
+
+impl Price {
+    /// The price of `base` in `quote`, `quote / base` in whole units of each token.
+    pub fn of(quote: &TokenAmount, base: &TokenAmount) -> Result<Price, Error> {
+        Price::from_raw(quote.raw, quote.decimals, base.raw, base.decimals)
+    }
+
+    /// The price of `base` in `quote`, from raw amounts and the decimals of their tokens.
+    pub fn from_raw(quote: U256, quote_decimals: u8, base: U256, base_decimals: u8) -> Result<Price, Error> {
+        if base.is_zero() {
+            return Err(ErrorKind::Amount("Can't price an amount of zero".to_string()).into());
+        }
+
+        let numer = to_big(quote) * pow(BigUint::from(10u32), base_decimals as usize);
+        let denom = to_big(base) * pow(BigUint::from(10u32), quote_decimals as usize);
+        Ok(Price(Ratio::new(numer, denom)))
+    }
+
+    pub fn numerator(&self) -> &BigUint {
+        self.0.numer()
+    }
+
+    pub fn denominator(&self) -> &BigUint {
+        self.0.denom()
+    }
+
+    /// The price of the quote token in the base token, `None` for a price of zero.
+    pub fn invert(&self) -> Option<Price> {
+        if self.0.numer().is_zero() {
+            None
+        } else {
+            Some(Price(self.0.recip()))
+        }
+    }
+
+    /// The price with `scale` fractional digits, rounded half up.
+    pub fn to_decimal_string(&self, scale: usize) -> String {
+        let scaled = self.0.numer() * pow(BigUint::from(10u32), scale);
+        let (mut digits, remainder) = scaled.div_rem(self.0.denom());
+        if remainder.clone() + remainder >= *self.0.denom() {
+            digits += BigUint::from(1u32);
+        }
+
+        let digits = format!("{:0>width$}", digits.to_string(), width = scale + 1);
+        if scale == 0 {
+            digits
+        } else {
+            let (whole, fraction) = digits.split_at(digits.len() - scale);
+            format!("{}.{}", whole, fraction)
+        }
+    }
+
+    /// The closest `f64`, for charts and the like.
+    pub fn to_f64(&self) -> f64 {
+        // Both sides must fit in an `f64` before dividing.
+        let shift = self.0.numer().bits().max(self.0.denom().bits()).saturating_sub(1000);
+        let numer = (self.0.numer().clone() >> shift).to_f64().expect("1000 bit integer");
+        let denom = (self.0.denom().clone() >> shift).to_f64().expect("1000 bit integer");
+        numer / denom
+    }
+}
+
+impl fmt::Display for Price {
+    /// The price with up to 18 fractional digits, without trailing zeros.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let decimal = self.to_decimal_string(18);
+        write!(f, "{}", decimal.trim_end_matches('0').trim_end_matches('.'))
+    }
+}
+
+impl FromStr for Price {
+    type Err = Error;
+
+    /// Parses a fraction like `"1/4000"`, or a decimal like `"0.00025"`.
+    fn from_str(s: &str) -> Result<Price, Error> {
+        let invalid = || Error::from(ErrorKind::Amount(format!("Invalid price: {:?}", s)));
+        let parse = |digits: &str| {
+            if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+                Err(invalid())
+            } else {
+                BigUint::from_str(digits).map_err(|_| invalid())
+            }
+        };
+
+        let (numer, denom) = match (s.find('/'), s.find('.')) {
+            (Some(i), None) => (parse(&s[..i])?, parse(&s[i + 1..])?),
+            (None, Some(i)) => {
+                let fraction = &s[i + 1..];
+                let digits = format!("{}{}", &s[..i], fraction);
+                (parse(&digits)?, pow(BigUint::from(10u32), fraction.len()))
+            },
+            (None, None) => (parse(s)?, BigUint::from(1u32)),
+            _ => return Err(invalid())
+        };
+
+        if denom.is_zero() {
+            return Err(invalid());
+        }
+        Ok(Price(Ratio::new(numer, denom)))
+    }
+}
+
+impl Serialize for Price {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("{}/{}", self.0.numer(), self.0.denom()))
+    }
+}
+
+impl<'de> Deserialize<'de> for Price {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Price, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Price::from_str(&s).map_err(de::Error::custom)
+    }
+}
+
+fn to_big(value: U256) -> BigUint {
+    BigUint::from_str(&value.to_string()).expect("U256 displays as decimal digits")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use serde_json;
+
+    use types::U256;
+    use super::Price;
+    use super::super::TokenAmount;
+
+    #[test]
+    fn prices_amounts_exactly() {
+        let ether = TokenAmount::parse_units("1", 18, "ETH").unwrap();
+        let tokens = TokenAmount::parse_units("3", 6, "USDT").unwrap();
+        let price = Price::of(&ether, &tokens).unwrap();
+
+        assert_eq!(price.to_string(), "0.333333333333333333");
+        assert_eq!(price.to_decimal_string(2), "0.33");
+        assert_eq!(Price::of(&tokens, &ether).unwrap().to_decimal_string(0), "3");
+        assert_eq!(price.invert().unwrap(), Price::from_str("3").unwrap());
+        assert_eq!(Price::from_str("2/3").unwrap().to_decimal_string(3), "0.667");
+        assert!((price.to_f64() - 1.0 / 3.0).abs() < 1e-15);
+
+        assert!(Price::of(&ether, &TokenAmount::ether(U256::zero())).is_err());
+        assert!(Price::from_raw(U256::zero(), 18, U256::one(), 18).unwrap().invert().is_none());
+    }
+
+    #[test]
+    fn parses_and_serializes() {
+        let price = Price::from_str("0.00025").unwrap();
+        assert_eq!(price, Price::from_str("1/4000").unwrap());
+        assert_eq!(price.to_string(), "0.00025");
+        assert_eq!(serde_json::to_string(&price).unwrap(), "\"1/4000\"");
+        assert_eq!(serde_json::from_str::<Price>("\"2/8000\"").unwrap(), price);
+
+        for invalid in &["", "1/0", "1/2/3", "1.5/2", "-1", "0x10", "."] {
+            assert!(Price::from_str(invalid).is_err(), "{}", invalid);
+        }
+    }
+}
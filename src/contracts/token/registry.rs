@@ -0,0 +1,225 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use serde_json;
+
+use error::Error;
+use types::{H160, U256};
+
+use super::ERC20;
+
+/// Chain id of the Ethereum mainnet.
+pub const MAINNET: u64 = 1;
+
+/// An entry of a token list in the standard format (https://tokenlists.org).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TokenInfo {
+    chain_id: u64,
+    address: H160,
+    name: String,
+    symbol: String,
+    decimals: u8,
+    #[serde(default, rename = "logoURI")]
+    logo_uri: Option<String>
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct TokenList {
+    tokens: Vec<TokenInfo>
+}
+
+/// Token metadata by chain and contract address.
+///
+/// Several contracts may use the same symbol, like the old and new MKR, or a scam copy of a
+/// popular token.  `by_symbol` resolves such conflicts with the address set by `prefer`, or else
+/// with the token registered first, so lists should be loaded from most to least trusted.
+#[derive(Debug, Clone, Default)]
+pub struct TokenRegistry {
+    tokens: HashMap<(u64, H160), ERC20>,
+    /// Addresses by chain and upper-cased symbol, in registration order.
+    symbols: HashMap<(u64, String), Vec<H160>>,
+    preferred: HashMap<(u64, String), H160>
+}
+
+impl TokenRegistry {
+    pub fn new() -> TokenRegistry {
+        TokenRegistry::default()
+    }
+
+    /// Registers a token, replacing any record for the same address.  Returns the replaced
+    /// record.
+    pub fn insert(&mut self, chain_id: u64, address: H160, token: ERC20) -> Option<ERC20> {
+        let previous = self.tokens.insert((chain_id, address), token.clone());
+
+        if let Some(ref previous) = previous {
+            if previous.symbol.to_uppercase() != token.symbol.to_uppercase() {
+                let key = (chain_id, previous.symbol.to_uppercase());
+                if let Some(addresses) = self.symbols.get_mut(&key) {
+                    addresses.retain(|a| *a != address);
+                }
+            }
+        }
+
+        let addresses = self.symbols.entry((chain_id, token.symbol.to_uppercase())).or_default();
+        if !addresses.contains(&address) {
+            addresses.push(address);
+        }
+
+        previous
+    }
+
+    /// Loads a list in the standard token list format, with tokens on any chain.  Tokens that
+    /// are already registered keep their record.  Returns the number of tokens added.
+    pub fn load_token_list(&mut self, json: &str) -> Result<usize, Error> {
+        let list = serde_json::from_str::<TokenList>(json)?;
+        let mut added = 0;
+
+        for info in list.tokens {
+            if self.tokens.contains_key(&(info.chain_id, info.address)) {
+                continue;
+            }
+
+            let token = ERC20 {
+                name: info.name,
+                symbol: info.symbol,
+                decimals: info.decimals,
+                total_supply: U256::zero(),
+                icon_url: info.logo_uri
+            };
+            self.insert(info.chain_id, info.address, token);
+            added += 1;
+        }
+
+        Ok(added)
+    }
+
+    pub fn load_token_list_file<P: AsRef<Path>>(&mut self, path: P) -> Result<usize, Error> {
+        self.load_token_list(&read_file(path)?)
+    }
+
+    /// Loads a JSON object of serialized `ERC20` records keyed by address, for one chain.
+    /// Tokens that are already registered keep their record.  Returns the number of tokens
+    /// added.
+    pub fn load_erc20_json(&mut self, chain_id: u64, json: &str) -> Result<usize, Error> {
+        let tokens = serde_json::from_str::<HashMap<H160, ERC20>>(json)?;
+        let mut tokens = tokens.into_iter().collect::<Vec<_>>();
+        // Maps have no order, so conflicts among them are resolved by address.
+        tokens.sort_by_key(|&(address, _)| address);
+
+        let mut added = 0;
+        for (address, token) in tokens {
+            if !self.tokens.contains_key(&(chain_id, address)) {
+                self.insert(chain_id, address, token);
+                added += 1;
+            }
+        }
+
+        Ok(added)
+    }
+
+    pub fn load_erc20_file<P: AsRef<Path>>(&mut self, chain_id: u64, path: P) -> Result<usize, Error> {
+        self.load_erc20_json(chain_id, &read_file(path)?)
+    }
+
+    /// Resolves `symbol` to `address` on the chain, whatever the registration order.
+    pub fn prefer(&mut self, chain_id: u64, symbol: &str, address: H160) {
+        self.preferred.insert((chain_id, symbol.to_uppercase()), address);
+    }
+
+    pub fn get(&self, chain_id: u64, address: &H160) -> Option<&ERC20> {
+        self.tokens.get(&(chain_id, *address))
+    }
+
+    /// The token a symbol resolves to, compared case-insensitively.
+    pub fn by_symbol(&self, chain_id: u64, symbol: &str) -> Option<(H160, &ERC20)> {
+        let key = (chain_id, symbol.to_uppercase());
+        let address = self.preferred.get(&key)
+            .filter(|a| self.get(chain_id, a).map(|t| t.symbol.to_uppercase() == key.1).unwrap_or(false))
+            .or_else(|| self.symbols.get(&key).and_then(|addresses| addresses.first()))?;
+
+        self.get(chain_id, address).map(|token| (*address, token))
+    }
+
+    /// Every address registered with a symbol, in registration order.
+    pub fn symbol_candidates(&self, chain_id: u64, symbol: &str) -> &[H160] {
+        self.symbols.get(&(chain_id, symbol.to_uppercase())).map(|a| &a[..]).unwrap_or(&[])
+    }
+
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+}
+
+fn read_file<P: AsRef<Path>>(path: P) -> Result<String, Error> {
+    let mut json = String::new();
+    File::open(path)?.read_to_string(&mut json)?;
+    Ok(json)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use types::{H160, U256};
+    use super::{TokenRegistry, MAINNET};
+
+    const OLD_MKR: &str = "c66ea802717bfb9833400264dd12c2bceaa34a6d";
+    const MKR: &str = "9f8f72aa9304c8b593d555f12ef6589cc3a579a2";
+
+    fn registry() -> TokenRegistry {
+        let mut registry = TokenRegistry::new();
+        registry.load_token_list(include_str!("../../../test_data/token_list.json")).unwrap();
+        registry.load_erc20_json(MAINNET, include_str!("../../../test_data/erc20_dump.json")).unwrap();
+        registry
+    }
+
+    #[test]
+    fn loads_token_lists_and_dumps() {
+        let registry = registry();
+        assert_eq!(registry.len(), 6);
+
+        let zrx = registry.get(MAINNET, &H160::from_str("e41d2489571d322189246dafa5ebde1f4699f498").unwrap()).unwrap();
+        assert_eq!((zrx.symbol.as_str(), zrx.decimals), ("ZRX", 18));
+        assert_eq!(zrx.icon_url.as_ref().unwrap(), "https://etherswap.io/icons/zrx.png");
+
+        let eos = registry.by_symbol(MAINNET, "eos").unwrap().1;
+        assert_eq!(eos.total_supply, U256::from_str("33b2e3c9fd0803ce8000000").unwrap());
+    }
+
+    #[test]
+    fn separates_chains() {
+        let registry = registry();
+        let (mainnet, _) = registry.by_symbol(MAINNET, "WETH").unwrap();
+        let (kovan, _) = registry.by_symbol(42, "WETH").unwrap();
+
+        assert_eq!(mainnet, H160::from_str("c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2").unwrap());
+        assert_eq!(kovan, H160::from_str("d0a1e359811322d97991e03f863a0c30c2cf029c").unwrap());
+        assert!(registry.get(42, &mainnet).is_none());
+        assert!(registry.by_symbol(3, "WETH").is_none());
+    }
+
+    #[test]
+    fn resolves_symbol_conflicts() {
+        let mut registry = registry();
+        let (old_mkr, mkr) = (H160::from_str(OLD_MKR).unwrap(), H160::from_str(MKR).unwrap());
+
+        assert_eq!(registry.symbol_candidates(MAINNET, "MKR"), &[mkr, old_mkr]);
+        assert_eq!(registry.by_symbol(MAINNET, "MKR").unwrap().0, mkr);
+
+        registry.prefer(MAINNET, "mkr", old_mkr);
+        assert_eq!(registry.by_symbol(MAINNET, "MKR").unwrap().0, old_mkr);
+
+        let mut renamed = registry.get(MAINNET, &old_mkr).unwrap().clone();
+        renamed.symbol = "MKR-OLD".to_string();
+        registry.insert(MAINNET, old_mkr, renamed);
+        assert_eq!(registry.symbol_candidates(MAINNET, "MKR"), &[mkr]);
+        assert_eq!(registry.by_symbol(MAINNET, "MKR").unwrap().0, mkr);
+    }
+}
@@ -0,0 +1,60 @@
+use std::str::FromStr;
+
+use contracts::abi::Contract;
+use types::{H160, H256};
+
+lazy_static! {
+    /// The canonical WETH9 contract on mainnet.
+    pub static ref WETH_ADDRESS: H160 = {
+        H160::from_str("c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2").unwrap()
+    };
+
+    /// The WETH9 events and functions on top of ERC20's.
+    pub static ref WETH_ABI: Contract = Contract::from_signatures(vec![
+        "event Deposit(address indexed dst, uint256 wad)",
+        "event Withdrawal(address indexed src, uint256 wad)",
+        "function deposit() payable",
+        "function withdraw(uint256 wad)"
+    ]).expect("Invalid WETH ABI");
+
+    pub static ref WETH_DEPOSIT_TOPIC: H256 = event_topic("Deposit");
+    pub static ref WETH_WITHDRAWAL_TOPIC: H256 = event_topic("Withdrawal");
+
+    // Methods
+    pub static ref DEPOSIT_ID: String = method_id("deposit");
+    pub static ref WITHDRAW_ID: String = method_id("withdraw");
+}
+
+fn method_id(name: &str) -> String {
+    WETH_ABI.function(name)
+        .map(|f| f.method_id())
+        .unwrap_or_else(|| panic!("WETH ABI is missing function {}", name))
+}
+
+fn event_topic(name: &str) -> H256 {
+    WETH_ABI.event(name)
+        .map(|e| e.topic())
+        .unwrap_or_else(|| panic!("WETH ABI is missing event {}", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use types::H256;
+    use super::*;
+
+    #[test]
+    fn ids_match_deployed_contract() {
+        assert_eq!(
+            *WETH_DEPOSIT_TOPIC,
+            H256::from_str("e1fffcc4923d04b559f4d29a8bfc6cda04eb5b0d3c460751c2402c5c5cc9109c").unwrap()
+        );
+        assert_eq!(
+            *WETH_WITHDRAWAL_TOPIC,
+            H256::from_str("7fcf532c15f0a6db0bd6d0e038bea71d30d808c7d98cb3bf7268a95bf5081b65").unwrap()
+        );
+        assert_eq!(*DEPOSIT_ID, "0xd0e30db0");
+        assert_eq!(*WITHDRAW_ID, "0x2e1a7d4d");
+    }
+}
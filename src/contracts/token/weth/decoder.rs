@@ -0,0 +1,124 @@
+use contracts::abi::{take_value, DecodeMode};
+use error::{Error, ErrorKind};
+use objects::{Log, ParityTrace, Transaction};
+use types::U256;
+
+use super::constants::*;
+use super::models::*;
+
+/// Decoder for the deposits and withdrawals of WETH9 and its copies.
+pub struct WETHDecoder;
+
+impl WETHDecoder {
+    pub fn decode_action(tx: &Transaction) -> Result<WETHAction, Error> {
+        WETHDecoder::decode_input(&tx.input, tx.value)
+    }
+
+    pub fn decode_trace_action(trace: &ParityTrace) -> Result<WETHAction, Error> {
+        WETHDecoder::decode_input(&trace.action.input, trace.action.value.unwrap_or_default())
+    }
+
+    /// Decodes a call sending `value` wei.  Empty input is a deposit through WETH9's fallback.
+    pub fn decode_input(input: &str, value: U256) -> Result<WETHAction, Error> {
+        if input.is_empty() || input == "0x" {
+            return Ok(WETHAction::Deposit(value));
+        }
+
+        let method_id = match input.get(..10) {
+            Some(id) => id.to_lowercase(),
+            None => return Err(decode_error(format!("Input is too short for a WETH call: {}", input)))
+        };
+
+        match method_id.as_str() {
+            id if id == *DEPOSIT_ID => {
+                WETH_ABI.function("deposit").expect("WETH function").decode_input(input, DecodeMode::Strict)?;
+                Ok(WETHAction::Deposit(value))
+            },
+            id if id == *WITHDRAW_ID => {
+                let function = WETH_ABI.function("withdraw").expect("WETH function");
+                let mut args = function.decode_input(input, DecodeMode::Strict)?.into_iter();
+                Ok(WETHAction::Withdraw(take_value(&mut args)?))
+            },
+            _ => Err(decode_error(format!("Not a WETH method: {}", method_id)))
+        }
+    }
+
+    /// Decodes a `Deposit` or `Withdrawal` log of WETH9 or a copy of it.  The events' `token`
+    /// is the contract that emitted the log, which isn't checked: any contract can emit these
+    /// topics, so compare it against `WETH_ADDRESS` or the wrappers you trust.
+    pub fn decode_event(log: &Log) -> Result<WETHEvent, Error> {
+        match log.topics.first() {
+            Some(t) if *t == *WETH_DEPOSIT_TOPIC => {
+                let fields = WETH_ABI.decode_log("Deposit", log)?;
+                Ok(WETHEvent::Deposit(WETHDeposit {
+                    token: log.address,
+                    owner: fields.address("dst")?,
+                    value: fields.uint("wad")?
+                }))
+            },
+            Some(t) if *t == *WETH_WITHDRAWAL_TOPIC => {
+                let fields = WETH_ABI.decode_log("Withdrawal", log)?;
+                Ok(WETHEvent::Withdrawal(WETHWithdrawal {
+                    token: log.address,
+                    owner: fields.address("src")?,
+                    value: fields.uint("wad")?
+                }))
+            },
+            Some(t) => Err(decode_error(format!("Not a WETH event: {:?}", t))),
+            None => Err(decode_error("Expected log topic vec of length (1)".to_string()))
+        }
+    }
+}
+
+fn decode_error(message: String) -> Error {
+    ErrorKind::Decoder(message).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use contracts::token::ERC20Transfer;
+    use objects::fixtures::log;
+    use types::{H160, H256, U256};
+    use super::WETHDecoder;
+    use super::super::constants::*;
+    use super::super::models::*;
+
+    const OWNER: &str = "461680c41786ec889ac9de7f5e89a2f4e0fcb156";
+
+    #[test]
+    fn decodes_conversions_as_mints_and_burns() {
+        let owner = H160::from_str(OWNER).unwrap();
+        let topics = |topic: H256| vec![topic, H256::from(owner)];
+
+        let deposit = log(*WETH_ADDRESS, topics(*WETH_DEPOSIT_TOPIC), &format!("0x{:064x}", 5));
+        let deposit = WETHDecoder::decode_event(&deposit).unwrap();
+        assert_eq!(deposit, WETHEvent::Deposit(WETHDeposit { token: *WETH_ADDRESS, owner, value: U256::from(5) }));
+        assert_eq!(
+            deposit.to_transfer(),
+            ERC20Transfer { token: *WETH_ADDRESS, from: H160::zero(), to: owner, value: U256::from(5) }
+        );
+
+        let withdrawal = log(*WETH_ADDRESS, topics(*WETH_WITHDRAWAL_TOPIC), &format!("0x{:064x}", 3));
+        let withdrawal = WETHDecoder::decode_event(&withdrawal).unwrap();
+        assert_eq!(
+            withdrawal.to_transfer(),
+            ERC20Transfer { token: *WETH_ADDRESS, from: owner, to: H160::zero(), value: U256::from(3) }
+        );
+
+        assert!(WETHDecoder::decode_event(&log(*WETH_ADDRESS, topics(*WETH_DEPOSIT_TOPIC), "0x")).is_err());
+    }
+
+    #[test]
+    fn decodes_calls() {
+        assert_eq!(WETHDecoder::decode_input("0xd0e30db0", U256::from(9)).unwrap(), WETHAction::Deposit(U256::from(9)));
+        assert_eq!(WETHDecoder::decode_input("0x", U256::from(9)).unwrap(), WETHAction::Deposit(U256::from(9)));
+        assert_eq!(
+            WETHDecoder::decode_input(&format!("{}{:064x}", *WITHDRAW_ID, 7), U256::zero()).unwrap(),
+            WETHAction::Withdraw(U256::from(7))
+        );
+        assert!(WETHDecoder::decode_input(&format!("{}00", *DEPOSIT_ID), U256::zero()).is_err());
+        assert!(WETHDecoder::decode_input("0xa9059cbb", U256::zero()).is_err());
+    }
+}
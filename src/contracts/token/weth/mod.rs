@@ -0,0 +1,5 @@
+pub mod constants;
+pub mod decoder;
+mod models;
+
+pub use self::models::{WETHAction, WETHDeposit, WETHEvent, WETHWithdrawal};
@@ -0,0 +1,72 @@
+use contracts::{ContractFunction, NamedFunction};
+use contracts::token::erc20::ERC20Transfer;
+use types::{H160, U256};
+
+/// Ether wrapped into WETH: the owner sent `value` wei and was credited `value` WETH.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WETHDeposit {
+    /// The WETH contract that emitted the event.
+    pub token: H160,
+    pub owner: H160,
+    pub value: U256
+}
+
+impl WETHDeposit {
+    /// The token side of the conversion, as a mint of WETH to the owner.
+    pub fn to_transfer(&self) -> ERC20Transfer {
+        ERC20Transfer { token: self.token, from: H160::zero(), to: self.owner, value: self.value }
+    }
+}
+
+/// WETH unwrapped into ether: `value` WETH was debited from the owner, who was sent `value` wei.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WETHWithdrawal {
+    /// The WETH contract that emitted the event.
+    pub token: H160,
+    pub owner: H160,
+    pub value: U256
+}
+
+impl WETHWithdrawal {
+    /// The token side of the conversion, as a burn of the owner's WETH.
+    pub fn to_transfer(&self) -> ERC20Transfer {
+        ERC20Transfer { token: self.token, from: self.owner, to: H160::zero(), value: self.value }
+    }
+}
+
+/// A conversion between ether and WETH.  WETH's ERC20 `Transfer` and `Approval` events are
+/// decoded by `ERC20Decoder`; WETH9 doesn't emit a `Transfer` for deposits or withdrawals.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WETHEvent {
+    Deposit(WETHDeposit),
+    Withdrawal(WETHWithdrawal)
+}
+
+impl WETHEvent {
+    /// The conversion as a WETH mint or burn, so that transfer accounting can treat WETH like
+    /// any other token.  The ether side appears in the transaction's value or its traces.
+    pub fn to_transfer(&self) -> ERC20Transfer {
+        match *self {
+            WETHEvent::Deposit(ref d) => d.to_transfer(),
+            WETHEvent::Withdrawal(ref w) => w.to_transfer()
+        }
+    }
+}
+
+/// A call to a WETH contract.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WETHAction {
+    /// Wraps the wei sent with the call.  Plain ether transfers to WETH9 deposit as well.
+    Deposit(U256),
+    /// Unwraps an amount of WETH.
+    Withdraw(U256)
+}
+
+impl NamedFunction for WETHAction {
+    fn get_function(&self) -> ContractFunction {
+        match *self {
+            WETHAction::Deposit(_) => ContractFunction::Mutable("WETH.deposit".to_string()),
+            WETHAction::Withdraw(_) => ContractFunction::Mutable("WETH.withdraw".to_string())
+        }
+    }
+}
@@ -1,16 +1,30 @@
 #![allow(unknown_lints)]
 #![allow(missing_docs)]
+#![allow(deprecated)]
 
 use serde_json;
 
 error_chain! {
   foreign_links {
+    Io(::std::io::Error);
     Json(serde_json::Error);
   }
   errors {
+    Amount(e: String) {
+        description("amount error"),
+        display("Amount error: {}", e)
+    }
+    Abi(e: String) {
+        description("abi error"),
+        display("ABI error: {}", e)
+    }
     Decoder(e: String) {
         description("decoder error"),
         display("Decoder error: {}", e)
     }
+    Signature(e: String) {
+        description("signature error"),
+        display("Signature error: {}", e)
+    }
   }
 }
\ No newline at end of file
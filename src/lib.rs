@@ -1,8 +1,11 @@
 extern crate bigdecimal;
 extern crate ethereum_types;
 extern crate fixed_hash;
+extern crate libsecp256k1;
 extern crate num;
 extern crate rustc_serialize;
+extern crate sha2;
+extern crate tiny_keccak;
 extern crate twox_hash;
 
 #[macro_use]
@@ -23,6 +26,14 @@ pub mod types;
 
 pub use error::{Error, ErrorKind};
 
+/// What the code generated by `ethereum-models-derive` refers to, so that crates using it don't
+/// need their own serde dependency.
+#[doc(hidden)]
+pub mod __derive {
+    pub extern crate serde;
+    pub use serde_derive::{Deserialize, Serialize};
+}
+
 use serde::de::{self, Deserialize, Deserializer};
 use serde::ser::Serializer;
 
@@ -33,9 +44,19 @@ fn opt_u64_from_str<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
     u64::from_str_radix(fixed_hash::clean_0x(&s), 16).map(Option::Some).map_err(de::Error::custom)
 }
 
+#[allow(dead_code)]
 fn u64_from_str<'de, D>(deserializer: D) -> Result<u64, D::Error>
     where D: Deserializer<'de>
 {
     let s = String::deserialize(deserializer)?;
     u64::from_str_radix(fixed_hash::clean_0x(&s), 16).map_err(de::Error::custom)
+}
+
+fn opt_u64_to_hex<S>(value: &Option<u64>, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer
+{
+    match *value {
+        Some(n) => serializer.serialize_str(&format!("0x{:x}", n)),
+        None => serializer.serialize_none()
+    }
 }
\ No newline at end of file
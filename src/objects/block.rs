@@ -1,14 +1,12 @@
-use std::u64;
 use std::cmp::Ordering;
 
 use types::{H160, H256, U128, U256};
-use rustc_serialize::hex::ToHex;
 
 use super::Transaction;
-use opt_u64_from_str;
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
 pub enum BlockTx {
     Hash(H256),
     Tx(Transaction)
@@ -71,28 +69,28 @@ mod tests {
     #[test]
     fn block_deserializes_only_hashes() {
         let block_json = include_str!("../../test_data/block_tx_hashes.json");
-        serde_json::from_str::<Block>(&block_json).unwrap();
+        serde_json::from_str::<Block>(block_json).unwrap();
     }
 
     #[test]
     fn block_deserializes_no_tx() {
         let block_json = include_str!("../../test_data/block_no_tx.json");
-        serde_json::from_str::<Block>(&block_json).unwrap();
+        serde_json::from_str::<Block>(block_json).unwrap();
     }
 
     #[test]
     fn block_number_deserializes() {
         let block_json = include_str!("../../test_data/block_no_tx.json");
-        let block = serde_json::from_str::<Block>(&block_json).unwrap();
+        let block = serde_json::from_str::<Block>(block_json).unwrap();
         let actual_block_number = 5110738;
         let deserialized_block_number = block.number.unwrap();
-        assert_eq!(deserialized_block_number, actual_block_number);
+        assert_eq!(deserialized_block_number, U128::from(actual_block_number));
     }
 
     #[test]
     fn block_deserializes_with_tx() {
         let block_json = include_str!("../../test_data/block_with_tx.json");
-        serde_json::from_str::<Block>(&block_json).unwrap();
+        serde_json::from_str::<Block>(block_json).unwrap();
     }
 
     #[test]
@@ -106,39 +104,39 @@ mod tests {
         let some_u256 = U256::from(1000);
 
         let block_5000000 = Block {
-            hash: Some(some_h256.clone()),
-            parent_hash: some_h256.clone(),
-            sha3_uncles: some_h256.clone(),
-            author: some_h160.clone(),
-            state_root: some_h256.clone(),
-            transactions_root: some_h256.clone(),
-            receipts_root: some_h256.clone(),
+            hash: Some(some_h256),
+            parent_hash: some_h256,
+            sha3_uncles: some_h256,
+            author: some_h160,
+            state_root: some_h256,
+            transactions_root: some_h256,
+            receipts_root: some_h256,
             number: Some(U128::from(5000000_u64)),
-            gas_used: some_u256.clone(),
-            gas_limit: some_u256.clone(),
+            gas_used: some_u256,
+            gas_limit: some_u256,
             extra_data: "none".to_string(),
-            timestamp: some_u256.clone(),
-            difficulty: some_u256.clone(),
-            total_difficulty: some_u256.clone(),
+            timestamp: some_u256,
+            difficulty: some_u256,
+            total_difficulty: some_u256,
             transactions: Vec::new(),
             size: None
         };
 
         let block_5000001 = Block {
-            hash: Some(some_h256.clone()),
-            parent_hash: some_h256.clone(),
-            sha3_uncles: some_h256.clone(),
-            author: some_h160.clone(),
-            state_root: some_h256.clone(),
-            transactions_root: some_h256.clone(),
-            receipts_root: some_h256.clone(),
-            number: Some(U128::from(5000000_u64)),
-            gas_used: some_u256.clone(),
-            gas_limit: some_u256.clone(),
+            hash: Some(some_h256),
+            parent_hash: some_h256,
+            sha3_uncles: some_h256,
+            author: some_h160,
+            state_root: some_h256,
+            transactions_root: some_h256,
+            receipts_root: some_h256,
+            number: Some(U128::from(5000001_u64)),
+            gas_used: some_u256,
+            gas_limit: some_u256,
             extra_data: "none".to_string(),
-            timestamp: some_u256.clone(),
-            difficulty: some_u256.clone(),
-            total_difficulty: some_u256.clone(),
+            timestamp: some_u256,
+            difficulty: some_u256,
+            total_difficulty: some_u256,
             transactions: Vec::new(),
             size: None
         };
@@ -158,66 +156,73 @@ mod tests {
         let some_u256 = U256::from(1000);
 
         let block_5000000 = Block {
-            hash: Some(some_h256.clone()),
-            parent_hash: some_h256.clone(),
-            sha3_uncles: some_h256.clone(),
-            author: some_h160.clone(),
-            state_root: some_h256.clone(),
-            transactions_root: some_h256.clone(),
-            receipts_root: some_h256.clone(),
+            hash: Some(some_h256),
+            parent_hash: some_h256,
+            sha3_uncles: some_h256,
+            author: some_h160,
+            state_root: some_h256,
+            transactions_root: some_h256,
+            receipts_root: some_h256,
             number: Some(U128::from(5000000_u64)),
-            gas_used: some_u256.clone(),
-            gas_limit: some_u256.clone(),
+            gas_used: some_u256,
+            gas_limit: some_u256,
             extra_data: "none".to_string(),
-            timestamp: some_u256.clone(),
-            difficulty: some_u256.clone(),
-            total_difficulty: some_u256.clone(),
+            timestamp: some_u256,
+            difficulty: some_u256,
+            total_difficulty: some_u256,
             transactions: Vec::new(),
             size: None
         };
 
         let block_5000001 = Block {
-            hash: Some(some_h256.clone()),
-            parent_hash: some_h256.clone(),
-            sha3_uncles: some_h256.clone(),
-            author: some_h160.clone(),
-            state_root: some_h256.clone(),
-            transactions_root: some_h256.clone(),
-            receipts_root: some_h256.clone(),
-            number: Some(U128::from(5000000_u64)),
-            gas_used: some_u256.clone(),
-            gas_limit: some_u256.clone(),
+            hash: Some(some_h256),
+            parent_hash: some_h256,
+            sha3_uncles: some_h256,
+            author: some_h160,
+            state_root: some_h256,
+            transactions_root: some_h256,
+            receipts_root: some_h256,
+            number: Some(U128::from(5000001_u64)),
+            gas_used: some_u256,
+            gas_limit: some_u256,
             extra_data: "none".to_string(),
-            timestamp: some_u256.clone(),
-            difficulty: some_u256.clone(),
-            total_difficulty: some_u256.clone(),
+            timestamp: some_u256,
+            difficulty: some_u256,
+            total_difficulty: some_u256,
             transactions: Vec::new(),
             size: None
         };
 
         let block_5000002 = Block {
-            hash: Some(some_h256.clone()),
-            parent_hash: some_h256.clone(),
-            sha3_uncles: some_h256.clone(),
-            author: some_h160.clone(),
-            state_root: some_h256.clone(),
-            transactions_root: some_h256.clone(),
-            receipts_root: some_h256.clone(),
-            number: Some(U128::from(5000000_u64)),
-            gas_used: some_u256.clone(),
-            gas_limit: some_u256.clone(),
+            hash: Some(some_h256),
+            parent_hash: some_h256,
+            sha3_uncles: some_h256,
+            author: some_h160,
+            state_root: some_h256,
+            transactions_root: some_h256,
+            receipts_root: some_h256,
+            number: Some(U128::from(5000002_u64)),
+            gas_used: some_u256,
+            gas_limit: some_u256,
             extra_data: "none".to_string(),
-            timestamp: some_u256.clone(),
-            difficulty: some_u256.clone(),
-            total_difficulty: some_u256.clone(),
+            timestamp: some_u256,
+            difficulty: some_u256,
+            total_difficulty: some_u256,
             transactions: Vec::new(),
             size: None
         };
 
-        let mut blocks = vec![block_5000000, block_5000002, block_5000001];
+        let mut blocks = [block_5000000, block_5000002, block_5000001];
         blocks.sort_by(|a, b| a.partial_cmp(b).unwrap());
-        let block_seq = blocks.iter().map(|b| b.number).collect::<Vec<Option<u64>>>();
-
-        assert_eq!(block_seq, vec![Some(U128::from(5000000_u64)), Some(U128::from(5000000_u64)), Some(U128::from(5000000_u64))]);
+        let block_seq = blocks.iter().map(|b| b.number).collect::<Vec<Option<U128>>>();
+
+        assert_eq!(
+            block_seq,
+            vec![
+                Some(U128::from(5000000_u64)),
+                Some(U128::from(5000001_u64)),
+                Some(U128::from(5000002_u64))
+            ]
+        );
     }
 }
\ No newline at end of file
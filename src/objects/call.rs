@@ -1,3 +1,5 @@
+use contracts::abi::{AbiValue, Function};
+use error::Error;
 use types::{H160, U256};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,6 +31,13 @@ impl TransactionCall {
         }
     }
 
+    /// A call of `function` on the contract at `to`, with the arguments ABI-encoded into
+    /// `data`.
+    pub fn function_call(to: H160, function: &Function, args: &[AbiValue]) -> Result<Self, Error> {
+        let data = function.encode_input_hex(args)?;
+        Ok(TransactionCall::empty().to(to).data(&data))
+    }
+
     pub fn from(mut self, v: H160) -> Self {
         self.from = Some(v);
         self
@@ -69,6 +78,7 @@ impl TransactionCall {
 mod tests {
     use std::str::FromStr;
     use types::{H160, U256};
+    use contracts::abi::{AbiValue, Function};
     use super::TransactionCall;
 
     #[test]
@@ -102,4 +112,29 @@ mod tests {
             tc.data, Some("0x".to_string())
         );
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn encodes_function_calls() {
+        let balance_of = "balanceOf(address,address) view returns (uint256)".parse::<Function>().unwrap();
+        let token = H160::from_str("e41d2489571d322189246dafa5ebde1f4699f498").unwrap();
+        let user = H160::from_str("a94f5374fce5edbc8e2a8697c15331677e6ebf0b").unwrap();
+
+        let tc = TransactionCall::function_call(
+            H160::from_str("8d12a197cb00d4747a1fe03395095ce2a5cc6819").unwrap(),
+            &balance_of,
+            &[AbiValue::Address(token), AbiValue::Address(user)]
+        ).unwrap().from(user).done();
+
+        assert_eq!(
+            tc.data,
+            Some("0xf7888aec\
+                  000000000000000000000000e41d2489571d322189246dafa5ebde1f4699f498\
+                  000000000000000000000000a94f5374fce5edbc8e2a8697c15331677e6ebf0b".to_string())
+        );
+        assert!(TransactionCall::function_call(token, &balance_of, &[AbiValue::Address(token)]).is_err());
+        assert!(
+            TransactionCall::function_call(token, &balance_of, &[AbiValue::Bool(true), AbiValue::Address(user)])
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,186 @@
+use types::H160;
+
+use super::trace::{CallType, ParityTrace};
+use {opt_u64_from_str, opt_u64_to_hex};
+
+/// Parameters of a `trace_filter` request.  The same filter can be applied offline to stored
+/// traces with `matches` and `apply`, following the node's semantics: the block range is
+/// inclusive, an empty address list matches any address, and `after`/`count` paginate over
+/// the matching traces.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceFilter {
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "opt_u64_to_hex",
+        deserialize_with = "opt_u64_from_str"
+    )]
+    pub from_block: Option<u64>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "opt_u64_to_hex",
+        deserialize_with = "opt_u64_from_str"
+    )]
+    pub to_block: Option<u64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub from_address: Vec<H160>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub to_address: Vec<H160>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<usize>
+}
+
+impl TraceFilter {
+    pub fn empty() -> Self {
+        TraceFilter::default()
+    }
+
+    pub fn from_block(mut self, v: u64) -> Self {
+        self.from_block = Some(v);
+        self
+    }
+
+    pub fn to_block(mut self, v: u64) -> Self {
+        self.to_block = Some(v);
+        self
+    }
+
+    pub fn from_address(mut self, v: H160) -> Self {
+        self.from_address.push(v);
+        self
+    }
+
+    pub fn to_address(mut self, v: H160) -> Self {
+        self.to_address.push(v);
+        self
+    }
+
+    pub fn after(mut self, v: usize) -> Self {
+        self.after = Some(v);
+        self
+    }
+
+    pub fn count(mut self, v: usize) -> Self {
+        self.count = Some(v);
+        self
+    }
+
+    pub fn done(self) -> Self {
+        self
+    }
+
+    /// Does a single trace satisfy the block range and address criteria?  Pagination
+    /// (`after`/`count`) is not considered.
+    pub fn matches(&self, trace: &ParityTrace) -> bool {
+        let in_range = self.from_block.map(|b| trace.block_number >= b).unwrap_or(true)
+            && self.to_block.map(|b| trace.block_number <= b).unwrap_or(true);
+
+        in_range
+            && matches_address(&self.from_address, trace_from(trace))
+            && matches_address(&self.to_address, trace_to(trace))
+    }
+
+    /// Applies the filter, including pagination, to a collection of traces.
+    pub fn apply<'a, I>(&self, traces: I) -> Vec<&'a ParityTrace>
+        where I: IntoIterator<Item = &'a ParityTrace>
+    {
+        traces.into_iter()
+            .filter(|t| self.matches(t))
+            .skip(self.after.unwrap_or(0))
+            .take(self.count.unwrap_or(usize::MAX))
+            .collect()
+    }
+}
+
+fn matches_address(addresses: &[H160], address: Option<H160>) -> bool {
+    addresses.is_empty() || address.map(|a| addresses.contains(&a)).unwrap_or(false)
+}
+
+fn trace_from(trace: &ParityTrace) -> Option<H160> {
+    match trace.action_type {
+        CallType::Suicide => trace.action.address,
+        _ => trace.action.from
+    }
+}
+
+fn trace_to(trace: &ParityTrace) -> Option<H160> {
+    match trace.action_type {
+        CallType::Create => trace.result.as_ref().and_then(|r| r.address),
+        CallType::Suicide => trace.action.refund_address,
+        _ => trace.action.to
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use serde_json;
+    use types::H160;
+    use objects::fixtures::proxy_traces;
+    use super::TraceFilter;
+
+    fn etherdelta() -> H160 {
+        H160::from_str("8d12a197cb00d4747a1fe03395095ce2a5cc6819").unwrap()
+    }
+
+    #[test]
+    fn serializes_request_params() {
+        let filter = TraceFilter::empty()
+            .from_block(4738797)
+            .to_block(4738800)
+            .to_address(etherdelta())
+            .count(10)
+            .done();
+
+        let json = serde_json::to_string(&filter).unwrap();
+        assert_eq!(
+            json,
+            r#"{"fromBlock":"0x484eed","toBlock":"0x484ef0","toAddress":["0x8d12a197cb00d4747a1fe03395095ce2a5cc6819"],"count":10}"#
+        );
+        assert_eq!(serde_json::from_str::<TraceFilter>(&json).unwrap(), filter);
+    }
+
+    #[test]
+    fn matches_calls_into_etherdelta_from_contracts() {
+        let traces = proxy_traces();
+        let filter = TraceFilter::empty()
+            .to_address(etherdelta())
+            .done();
+
+        let calls = filter.apply(&traces)
+            .into_iter()
+            .filter(|t| t.is_internal())
+            .count();
+
+        assert_eq!(calls, 7);
+    }
+
+    #[test]
+    fn filters_by_block_range_and_sender() {
+        let traces = proxy_traces();
+        let sender = H160::from_str("000029c8bf6f0790f2bbc8afee6726e2b554a58e").unwrap();
+
+        assert_eq!(TraceFilter::empty().from_address(sender).done().apply(&traces).len(), 1);
+        assert!(TraceFilter::empty().from_block(4738798).done().apply(&traces).is_empty());
+        assert!(TraceFilter::empty().to_block(4738796).done().apply(&traces).is_empty());
+    }
+
+    #[test]
+    fn paginates_matches() {
+        let traces = proxy_traces();
+        let page = TraceFilter::empty()
+            .to_address(etherdelta())
+            .after(5)
+            .count(10)
+            .done()
+            .apply(&traces);
+
+        assert_eq!(page.len(), 2);
+        assert_eq!(page[0].trace_address, vec![5]);
+    }
+}
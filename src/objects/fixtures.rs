@@ -0,0 +1,16 @@
+use serde_json;
+
+use types::{H160, H256};
+use super::{Log, ParityTrace};
+
+/// The traces of a transaction in which a proxy contract checks balances and available
+/// volumes on EtherDelta, then fills two orders (`test_data/etherdelta_proxy_trace.json`).
+pub fn proxy_traces() -> Vec<ParityTrace> {
+    let traces = include_str!("../../test_data/etherdelta_proxy_trace.json");
+    serde_json::from_str::<Vec<ParityTrace>>(traces).unwrap()
+}
+
+/// A mined log emitted by `address`, with no position in the chain.
+pub fn log(address: H160, topics: Vec<H256>, data: &str) -> Log {
+    Log { address, topics, data: data.to_string(), ..Default::default() }
+}
@@ -0,0 +1,233 @@
+use std::collections::HashMap;
+
+use types::{H160, H256, U256};
+
+use super::trace::{CallType, ParityTrace};
+
+/// Gas accounting for a single call frame of a transaction's trace tree.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FrameGas {
+    pub transaction_hash: H256,
+    pub block_number: u64,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub trace_address: Vec<u8>,
+    /// The called contract, or the created contract's address for `create` frames.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub callee: Option<H160>,
+    /// The 4-byte method id (`0x`-prefixed) of the frame's input, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selector: Option<String>,
+    /// Gas used by the frame, including all of its subcalls.
+    pub inclusive: U256,
+    /// Gas used by the frame's own code, i.e. `inclusive` minus the subcalls' `inclusive`.
+    pub exclusive: U256
+}
+
+impl FrameGas {
+    /// Computes per-frame gas from a set of traces, which may span several transactions
+    /// and blocks.  Parity doesn't report the gas used by failed frames.  A frame that failed
+    /// with an exceptional halt, like running out of gas, consumed its whole `gas`
+    /// allowance, and is charged that.  A reverted frame returned what it didn't use, so it's
+    /// charged only what its subcalls used, which is a lower bound; its own code is charged
+    /// nothing.  Self-destructs use no gas of their own.
+    pub fn from_traces(traces: &[ParityTrace]) -> Vec<FrameGas> {
+        // Children come before their parents, so a reverted frame's subcalls are all counted
+        // by the time it's charged.
+        let mut by_depth = traces.iter().collect::<Vec<&ParityTrace>>();
+        by_depth.sort_by_key(|t| usize::MAX - t.trace_address.len());
+
+        let mut inclusive_by_frame: HashMap<(H256, Vec<u8>), U256> = HashMap::new();
+        let mut children_by_frame: HashMap<(H256, Vec<u8>), U256> = HashMap::new();
+
+        for t in by_depth {
+            let key = (t.transaction_hash, t.trace_address.clone());
+            let children = children_by_frame.get(&key).cloned().unwrap_or_else(U256::zero);
+            let inclusive = inclusive_gas(t, children);
+            inclusive_by_frame.insert(key, inclusive);
+
+            if !t.trace_address.is_empty() {
+                let parent = t.trace_address[..t.trace_address.len() - 1].to_vec();
+                let entry = children_by_frame
+                    .entry((t.transaction_hash, parent))
+                    .or_insert_with(U256::zero);
+                *entry = entry.saturating_add(inclusive);
+            }
+        }
+
+        traces.iter()
+            .map(|t| {
+                let key = (t.transaction_hash, t.trace_address.clone());
+                let inclusive = inclusive_by_frame[&key];
+                let children = children_by_frame.get(&key).cloned().unwrap_or_else(U256::zero);
+
+                FrameGas {
+                    transaction_hash: t.transaction_hash,
+                    block_number: t.block_number,
+                    trace_address: t.trace_address.clone(),
+                    callee: callee(t),
+                    selector: selector(t),
+                    inclusive,
+                    exclusive: inclusive.saturating_sub(children)
+                }
+            })
+            .collect()
+    }
+}
+
+/// Aggregated gas usage of a group of frames.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GasUsage {
+    /// Number of frames aggregated.
+    pub calls: u64,
+    pub inclusive: U256,
+    pub exclusive: U256
+}
+
+impl GasUsage {
+    fn add(&mut self, frame: &FrameGas) {
+        self.calls += 1;
+        self.inclusive = self.inclusive.saturating_add(frame.inclusive);
+        self.exclusive = self.exclusive.saturating_add(frame.exclusive);
+    }
+}
+
+/// Gas usage aggregated by callee address and by (callee, method id).  Exclusive gas is
+/// what should be used to compare how much of a transaction was spent in each contract,
+/// e.g. inside EtherDelta versus inside token contracts; inclusive gas double counts nested
+/// calls.
+#[derive(Debug, Clone, Default)]
+pub struct GasReport {
+    pub by_callee: HashMap<H160, GasUsage>,
+    pub by_selector: HashMap<(H160, String), GasUsage>
+}
+
+impl GasReport {
+    pub fn from_traces(traces: &[ParityTrace]) -> GasReport {
+        let mut report = GasReport::default();
+
+        for frame in FrameGas::from_traces(traces) {
+            report.add_frame(&frame);
+        }
+
+        report
+    }
+
+    /// Same as `from_traces`, but only frames with `from_block <= block_number <= to_block`
+    /// are aggregated.
+    pub fn for_block_range(traces: &[ParityTrace], from_block: u64, to_block: u64) -> GasReport {
+        let traces = traces.iter()
+            .filter(|t| t.block_number >= from_block && t.block_number <= to_block)
+            .cloned()
+            .collect::<Vec<ParityTrace>>();
+
+        GasReport::from_traces(&traces)
+    }
+
+    pub fn add_frame(&mut self, frame: &FrameGas) {
+        if let Some(callee) = frame.callee {
+            self.by_callee.entry(callee).or_default().add(frame);
+
+            if let Some(ref selector) = frame.selector {
+                self.by_selector.entry((callee, selector.clone())).or_default().add(frame);
+            }
+        }
+    }
+
+    pub fn callee(&self, address: &H160) -> Option<&GasUsage> {
+        self.by_callee.get(address)
+    }
+
+    pub fn selector(&self, address: &H160, method_id: &str) -> Option<&GasUsage> {
+        self.by_selector.get(&(*address, method_id.to_string()))
+    }
+}
+
+/// The gas used by a frame whose subcalls used `children`.
+fn inclusive_gas(trace: &ParityTrace, children: U256) -> U256 {
+    match (trace.result.as_ref(), trace.error.as_deref()) {
+        (Some(result), _) => result.gas_used,
+        (None, Some("Reverted")) => children,
+        (None, Some(_)) => trace.action.gas.unwrap_or_else(U256::zero),
+        (None, None) => U256::zero()
+    }
+}
+
+fn callee(trace: &ParityTrace) -> Option<H160> {
+    match trace.action_type {
+        CallType::Create => trace.result.as_ref().and_then(|r| r.address),
+        _ => trace.action.to
+    }
+}
+
+fn selector(trace: &ParityTrace) -> Option<String> {
+    let input = &trace.action.input;
+    if input.len() >= 10 && input.starts_with("0x") {
+        Some(input[..10].to_string())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use types::{H160, U256};
+    use objects::fixtures::proxy_traces;
+    use super::{FrameGas, GasReport};
+
+    #[test]
+    fn computes_inclusive_and_exclusive_frame_gas() {
+        let frames = FrameGas::from_traces(&proxy_traces());
+
+        assert_eq!(frames.len(), 8);
+        assert_eq!(frames[0].inclusive, U256::from(0x260ea));
+        assert_eq!(frames[0].exclusive, U256::from(0x260ea - 3 * 0x21f7 - 2 * 0x4f4 - 2 * 0xb78a));
+        assert_eq!(frames[1].inclusive, frames[1].exclusive);
+        assert_eq!(frames[1].selector, Some("0xfb6e155f".to_string()));
+    }
+
+    #[test]
+    fn charges_failed_frames_by_how_they_failed() {
+        let mut traces = proxy_traces();
+        for &(i, error) in &[(0, "Reverted"), (1, "Reverted"), (5, "Out of gas")] {
+            traces[i].result = None;
+            traces[i].error = Some(error.to_string());
+        }
+        let frames = FrameGas::from_traces(&traces);
+
+        // A reverted frame is charged what its subcalls used, and nothing for its own code.
+        assert_eq!(frames[1].inclusive, U256::zero());
+        assert_eq!(frames[5].inclusive, traces[5].action.gas.unwrap());
+        let children = frames[1..].iter().fold(U256::zero(), |sum, f| sum + f.inclusive);
+        assert_eq!(frames[0].inclusive, children);
+        assert_eq!(frames[0].exclusive, U256::zero());
+    }
+
+    #[test]
+    fn aggregates_gas_by_callee_and_selector() {
+        let report = GasReport::from_traces(&proxy_traces());
+        let etherdelta = H160::from_str("8d12a197cb00d4747a1fe03395095ce2a5cc6819").unwrap();
+        let proxy = H160::from_str("f43b43869dd95e53923b30a79902915c1faa47d3").unwrap();
+
+        let ed_usage = report.callee(&etherdelta).unwrap();
+        assert_eq!(ed_usage.calls, 7);
+        assert_eq!(ed_usage.exclusive, U256::from(3 * 0x21f7 + 2 * 0x4f4 + 2 * 0xb78a));
+
+        let trades = report.selector(&etherdelta, "0x0a19b14a").unwrap();
+        assert_eq!(trades.calls, 2);
+        assert_eq!(trades.exclusive, U256::from(2 * 0xb78a));
+
+        let proxy_usage = report.callee(&proxy).unwrap();
+        assert_eq!(proxy_usage.inclusive, U256::from(0x260ea));
+    }
+
+    #[test]
+    fn filters_by_block_range() {
+        let traces = proxy_traces();
+
+        assert!(GasReport::for_block_range(&traces, 0, 4738796).by_callee.is_empty());
+        assert_eq!(GasReport::for_block_range(&traces, 4738797, 4738797).by_callee.len(), 2);
+    }
+}
@@ -1,6 +1,5 @@
 use std::hash::{Hash, Hasher};
 
-use rustc_serialize::hex::ToHex;
 use twox_hash::XxHash;
 
 use types::{H160, H256, U256};
@@ -19,7 +18,7 @@ pub trait LogLike {
     }
 
     fn raw_event_data(&self) -> &str {
-        &self.event_log().data.as_str()
+        self.event_log().data.as_str()
     }
 
     fn block_hash(&self) -> Option<&H256> {
@@ -72,7 +71,7 @@ pub trait LogLike {
 }
 
 /// A log produced after a transaction's execution.
-#[derive(Debug, Hash, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Default, Hash, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Log {
     pub address: H160,
@@ -1,12 +1,19 @@
 mod block;
 mod call;
+mod filter;
+mod gas;
 mod log;
 mod transaction;
 
 pub mod trace;
 
+#[cfg(test)]
+pub mod fixtures;
+
 pub use self::block::{Block, BlockNumber, BlockTx};
 pub use self::call::TransactionCall;
+pub use self::filter::TraceFilter;
+pub use self::gas::{FrameGas, GasReport, GasUsage};
 pub use self::log::{Log, LogLike};
 pub use self::trace::ParityTrace;
 pub use self::transaction::{
@@ -24,6 +24,10 @@ pub struct ParityTrace {
     pub action: Action,
     pub block_hash: H256,
     pub block_number: u64,
+    /// Why the frame failed, like `"Reverted"`.  Self-destructs have no result either, so
+    /// this, not a missing result, is what marks a failed frame.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub result: Option<ActionResult>,
     pub subtraces: u8,
@@ -36,6 +40,16 @@ pub struct ParityTrace {
 }
 
 impl ParityTrace {
+    /// Was this frame initiated by a contract, rather than by the transaction's sender?
+    pub fn is_internal(&self) -> bool {
+        !self.trace_address.is_empty()
+    }
+
+    /// Did this frame fail?  The frames it called are undone with it.
+    pub fn is_failed(&self) -> bool {
+        self.error.is_some()
+    }
+
     pub fn to_hash(&self) -> u64 {
         let mut hasher = XxHash::default();
         self.hash(&mut hasher);
@@ -71,6 +85,8 @@ pub struct Action {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub from: Option<H160>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<H160>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub balance: Option<U256>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub refund_address: Option<H160>,
@@ -106,6 +122,6 @@ mod tests {
     #[test]
     fn decodes_traces() {
         let traces = include_str!("../../test_data/parity_trace.json");
-        let decoded_traces = serde_json::from_str::<Vec<ParityTrace>>(&traces);
+        let _decoded_traces = serde_json::from_str::<Vec<ParityTrace>>(traces);
     }
 }
\ No newline at end of file
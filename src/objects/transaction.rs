@@ -1,10 +1,8 @@
 use std::hash::{Hash, Hasher};
 
 use types::{H160, H256, U128, U256};
-use rustc_serialize::hex::ToHex;
 use twox_hash::XxHash;
 
-use {u64_from_str, opt_u64_from_str};
 use objects::Log;
 
 /// A trait for all transaction-data-containing structures, but that are themselves
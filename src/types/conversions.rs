@@ -2,7 +2,6 @@ use std::str::FromStr;
 
 use bigdecimal::{BigDecimal, ParseBigDecimalError};
 use ethereum_types::U256;
-use num::Num;
 
 /// Used to convert between canonical Ethereum units of measure.
 pub enum EthereumUnit {
@@ -34,7 +33,7 @@ impl EthereumUnit {
             EthereumUnit::Gether(_) => 27,
             EthereumUnit::Tether(_) => 30
         };
-        BigDecimal::from(1.0 * 10f32.powi(factor as i32))
+        BigDecimal::from(1.0 * 10f32.powi(factor))
     }
 
     pub fn value(&self) -> &BigDecimal {
@@ -55,7 +54,7 @@ impl EthereumUnit {
 
     pub fn from_wei(amount: &U256) -> Result<EthereumUnit, ParseBigDecimalError> {
         BigDecimal::from_str(&format!("{:?}", &amount))
-            .map(|n| EthereumUnit::Wei(n))
+            .map(EthereumUnit::Wei)
     }
 
     pub fn to_wei(&self) -> EthereumUnit {
@@ -132,7 +131,7 @@ impl EthereumUnit {
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
-    use bigdecimal::{BigDecimal, ParseBigDecimalError};
+    use bigdecimal::BigDecimal;
     use ethereum_types::U256;
 
     use super::EthereumUnit;